@@ -0,0 +1,25 @@
+create table `webhook` (
+    id int unsigned not null primary key auto_increment,
+    url varchar(512) not null,
+    secret varchar(128) not null,
+    events varchar(256) not null default '*',
+    active boolean not null default true,
+    created_at timestamp not null default current_timestamp()
+);
+
+create table `webhook_delivery` (
+    id int unsigned not null primary key auto_increment,
+    webhook_id int unsigned not null,
+    event varchar(64) not null,
+    payload text not null,
+    attempts int unsigned not null default 0,
+    next_attempt_at timestamp not null default current_timestamp(),
+    delivered_at timestamp null default null,
+    last_error varchar(512) null default null,
+    created_at timestamp not null default current_timestamp(),
+    constraint fk_webhook_delivery_webhook
+        foreign key (webhook_id) references `webhook`(id)
+        on delete cascade on update no action
+);
+
+create index idx_webhook_delivery_pending on `webhook_delivery` (delivered_at, next_attempt_at);
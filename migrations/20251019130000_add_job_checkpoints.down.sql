@@ -0,0 +1 @@
+drop table `job_checkpoint`;
\ No newline at end of file
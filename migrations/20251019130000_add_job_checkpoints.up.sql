@@ -0,0 +1,5 @@
+create table `job_checkpoint` (
+    job varchar(64) not null primary key,
+    last_id int unsigned not null,
+    updated_at timestamp not null default current_timestamp() on update current_timestamp()
+);
@@ -0,0 +1,3 @@
+alter table `stats`
+    drop index idx_stats_leaderboard_rank,
+    drop column leaderboard_rank;
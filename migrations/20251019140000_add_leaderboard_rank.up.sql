@@ -0,0 +1,11 @@
+alter table `stats`
+    add column leaderboard_rank int unsigned null,
+    add index idx_stats_leaderboard_rank (leaderboard_rank);
+
+update `stats` s
+left join (
+    select player_id, row_number() over (order by rating desc, player_id asc) as position
+    from `stats`
+    where (wins + losses) > 0
+) ranked on ranked.player_id = s.player_id
+set s.leaderboard_rank = ranked.position;
@@ -0,0 +1 @@
+drop table `player_name_history`;
@@ -0,0 +1,11 @@
+create table `player_name_history` (
+    id int unsigned not null primary key auto_increment,
+    player_id int unsigned not null,
+    steam_name varchar(64) not null,
+    replaced_at timestamp not null default current_timestamp(),
+    constraint fk_player_name_history_player
+        foreign key (player_id) references `player`(id)
+        on delete cascade on update no action
+);
+
+create index idx_player_name_history_steam_name on `player_name_history` (steam_name);
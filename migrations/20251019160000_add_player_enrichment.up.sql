@@ -0,0 +1,12 @@
+create table `player_enrichment` (
+    player_id int unsigned not null primary key,
+    attempts int unsigned not null default 0,
+    next_attempt_at timestamp not null default current_timestamp(),
+    last_error varchar(512) null default null,
+    created_at timestamp not null default current_timestamp(),
+    constraint fk_player_enrichment_player
+        foreign key (player_id) references `player`(id)
+        on delete cascade on update no action
+);
+
+create index idx_player_enrichment_due on `player_enrichment` (next_attempt_at);
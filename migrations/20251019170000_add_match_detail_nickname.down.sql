@@ -0,0 +1 @@
+alter table `match_detail` drop column nickname;
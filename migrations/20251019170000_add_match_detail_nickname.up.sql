@@ -0,0 +1 @@
+alter table `match_detail` add column nickname varchar(64) null default null after player_id;
@@ -0,0 +1 @@
+alter table `job_checkpoint` drop column until_id;
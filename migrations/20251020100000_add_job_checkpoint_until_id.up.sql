@@ -0,0 +1 @@
+alter table `job_checkpoint` add column until_id int unsigned null default null;
@@ -0,0 +1,2 @@
+drop index idx_match_processed_at on `match`;
+alter table `match` drop column processed_at;
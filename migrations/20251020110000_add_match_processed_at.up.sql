@@ -0,0 +1,6 @@
+alter table `match` add column processed_at timestamp null default null;
+
+update `match` m set processed_at = m.match_date
+where exists (select 1 from `match_detail` md where md.match_id = m.id);
+
+create index idx_match_processed_at on `match` (processed_at);
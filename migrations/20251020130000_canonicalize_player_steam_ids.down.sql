@@ -0,0 +1,2 @@
+-- Merged players can't be told apart again, so this migration is not reverted.
+select 1;
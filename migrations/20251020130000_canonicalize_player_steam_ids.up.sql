@@ -0,0 +1,62 @@
+-- Players used to be stored with whichever universe the client reported. Legacy
+-- STEAM_1 players are merged into their STEAM_0 duplicate when one exists, the
+-- rest are renamed. Merged ratings keep the side with more matches until the
+-- next reprocess, and the legacy name stays searchable as a previous name.
+create temporary table `player_steam_id_merge` as
+select
+    legacy.id as legacy_id,
+    canonical.id as canonical_id,
+    legacy.steam_name as legacy_steam_name,
+    canonical.steam_name as canonical_steam_name,
+    coalesce(ls.rating, 0) as rating,
+    coalesce(ls.uncertainty, 0) as uncertainty,
+    coalesce(ls.wins, 0) as wins,
+    coalesce(ls.losses, 0) as losses,
+    coalesce(ls.total_frags, 0) as total_frags,
+    coalesce(ls.total_deaths, 0) as total_deaths
+from `player` legacy
+join `player` canonical on canonical.steam_id = concat('STEAM_0:', substring(legacy.steam_id, 9))
+left join `stats` ls on ls.player_id = legacy.id
+where left(legacy.steam_id, 8) = 'STEAM_1:';
+
+update `match_detail` md
+join `player_steam_id_merge` m on md.player_id = m.legacy_id
+set md.player_id = m.canonical_id;
+
+update `player_name_history` h
+join `player_steam_id_merge` m on h.player_id = m.legacy_id
+set h.player_id = m.canonical_id;
+
+update `stats` s
+join `player_steam_id_merge` m on s.player_id = m.canonical_id
+set s.rating = m.rating, s.uncertainty = m.uncertainty
+where m.wins + m.losses > s.wins + s.losses;
+
+update `stats` s
+join `player_steam_id_merge` m on s.player_id = m.canonical_id
+set
+    s.wins = s.wins + m.wins,
+    s.losses = s.losses + m.losses,
+    s.total_frags = s.total_frags + m.total_frags,
+    s.total_deaths = s.total_deaths + m.total_deaths;
+
+insert into `player_name_history` (player_id, steam_name)
+select m.canonical_id, m.legacy_steam_name
+from `player_steam_id_merge` m
+where m.legacy_steam_name <> m.canonical_steam_name
+    and not exists (
+        select 1 from `player_name_history` h
+        where h.player_id = m.canonical_id and h.steam_name = m.legacy_steam_name
+    );
+
+delete s from `stats` s
+join `player_steam_id_merge` m on s.player_id = m.legacy_id;
+
+delete p from `player` p
+join `player_steam_id_merge` m on p.id = m.legacy_id;
+
+drop temporary table `player_steam_id_merge`;
+
+update `player`
+set steam_id = concat('STEAM_0:', substring(steam_id, 9))
+where left(steam_id, 8) = 'STEAM_1:';
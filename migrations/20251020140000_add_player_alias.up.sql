@@ -0,0 +1,20 @@
+create table `player_alias` (
+    id int unsigned not null primary key auto_increment,
+    player_id int unsigned not null,
+    nickname varchar(64) not null,
+    first_seen_at timestamp not null default current_timestamp(),
+    last_seen_at timestamp not null default current_timestamp(),
+    constraint uq_player_alias unique (player_id, nickname),
+    constraint fk_player_alias_player
+        foreign key (player_id) references `player`(id)
+        on delete cascade on update no action
+);
+
+create index idx_player_alias_nickname on `player_alias` (nickname);
+
+insert into `player_alias` (player_id, nickname, first_seen_at, last_seen_at)
+select md.player_id, md.nickname, min(m.match_date), max(m.match_date)
+from `match_detail` md
+join `match` m on md.match_id = m.id
+where md.nickname is not null
+group by md.player_id, md.nickname;
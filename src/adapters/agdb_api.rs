@@ -1,13 +1,27 @@
-use crate::common::error::{AppError, ServiceResult};
+use crate::{
+    common::{
+        error::{AppError, ServiceResult},
+        metrics,
+    },
+    settings::AppSettings,
+};
 use reqwest::StatusCode;
 use serde::Deserialize;
 use std::{fmt::Display, sync::LazyLock};
 
 fn make_url<T: Display>(steam_id: T) -> String {
-    format!("https://agdb.7mochi.ru/players/{steam_id}")
+    format!(
+        "{}/players/{steam_id}",
+        AppSettings::get().services.agdb_api_url
+    )
 }
 
-static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(AppSettings::get().services.request_timeout)
+        .build()
+        .expect("Failed to build the AGDB API client")
+});
 
 #[derive(Debug, Deserialize)]
 pub struct AGDBPlayer {
@@ -22,7 +36,7 @@ pub struct AGDBPlayer {
 
 pub async fn fetch_player_info(steam_id: String) -> ServiceResult<AGDBPlayer> {
     let url = make_url(steam_id);
-    let response = CLIENT.get(url).send().await?;
+    let response = metrics::track_external("agdb", CLIENT.get(url).send()).await?;
     match response.status() {
         StatusCode::BAD_REQUEST => Err(AppError::AGDBInvalidSteamID),
         StatusCode::NOT_FOUND => Err(AppError::AGDBPlayerNotFound),
@@ -0,0 +1,53 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+use crate::settings::AppSettings;
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(AppSettings::get().services.request_timeout)
+        .build()
+        .expect("Failed to build the Discord API client")
+});
+
+fn make_url(path: &str) -> String {
+    format!("{}{path}", AppSettings::get().services.discord_api_url)
+}
+
+#[derive(Serialize)]
+struct CreateMessage<'a> {
+    content: &'a str,
+}
+
+pub async fn register_commands(
+    application_id: &str,
+    bot_token: &str,
+    commands: &Value,
+) -> anyhow::Result<()> {
+    let url = make_url(&format!("/applications/{application_id}/commands"));
+    CLIENT
+        .put(url)
+        .header("Authorization", format!("Bot {bot_token}"))
+        .json(commands)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+pub async fn create_message(
+    channel_id: &str,
+    bot_token: &str,
+    content: &str,
+) -> anyhow::Result<()> {
+    let url = make_url(&format!("/channels/{channel_id}/messages"));
+    CLIENT
+        .post(url)
+        .header("Authorization", format!("Bot {bot_token}"))
+        .json(&CreateMessage { content })
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
@@ -1,12 +1,26 @@
-use crate::common::error::ServiceResult;
+use crate::{
+    common::{error::ServiceResult, metrics},
+    settings::AppSettings,
+};
 use serde::Deserialize;
 use std::fmt::Display;
 use std::net::IpAddr;
+use std::sync::LazyLock;
 
 fn make_url<T: Display>(ip: T) -> String {
-    format!("http://ip-api.com/json/{ip}?fields=countryCode,lat,lon")
+    format!(
+        "{}/json/{ip}?fields=countryCode,lat,lon",
+        AppSettings::get().services.ip_api_url
+    )
 }
 
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(AppSettings::get().services.request_timeout)
+        .build()
+        .expect("Failed to build the IP API client")
+});
+
 #[derive(Debug, Deserialize)]
 pub struct IPLocation {
     #[serde(rename = "countryCode")]
@@ -22,6 +36,9 @@ pub async fn get_ip_info(ip_address: IpAddr) -> ServiceResult<IPLocation> {
         true => make_url(""),
         false => make_url(ip_address),
     };
-    let location: IPLocation = reqwest::get(url).await?.json().await?;
+    let location: IPLocation = metrics::track_external("ip_api", async {
+        CLIENT.get(url).send().await?.json().await
+    })
+    .await?;
     Ok(location)
 }
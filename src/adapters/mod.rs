@@ -1,2 +1,5 @@
 pub mod agdb_api;
+pub mod discord_api;
 pub mod ip_api;
+pub mod steam_api;
+pub mod webhook;
@@ -0,0 +1,116 @@
+use crate::{
+    common::{
+        error::{AppError, ServiceResult},
+        metrics,
+    },
+    settings::AppSettings,
+};
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+const VANITY_URL_RESOLVED: u8 = 1;
+
+pub const MAX_SUMMARIES_PER_REQUEST: u32 = 100;
+
+// The avatar Steam shows for profiles without one, used until a profile is fetched.
+pub const DEFAULT_AVATAR_URL: &str =
+    "https://avatars.steamstatic.com/fef49e7fa7e1997310d705b2a6158ff8dc1cdfeb_full.jpg";
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(AppSettings::get().services.request_timeout)
+        .build()
+        .expect("Failed to build the Steam API client")
+});
+
+#[derive(Debug, Deserialize)]
+struct PlayerSummariesResponse {
+    response: PlayerSummaries,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlayerSummaries {
+    players: Vec<PlayerSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlayerSummary {
+    pub steamid: String,
+    pub personaname: String,
+    pub avatarfull: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolveVanityUrlResponse {
+    response: ResolvedVanityUrl,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolvedVanityUrl {
+    success: u8,
+    steamid: Option<String>,
+}
+
+fn api_key() -> ServiceResult<&'static str> {
+    match AppSettings::get().steam_api_key.as_ref() {
+        Some(steam_api_key) => Ok(steam_api_key.expose()),
+        None => Err(AppError::InternalServerError(
+            "STEAM_API_KEY is not configured",
+        )),
+    }
+}
+
+// Profiles that don't exist are left out of the response.
+pub async fn get_player_summaries(steam_ids: &[u64]) -> ServiceResult<Vec<PlayerSummary>> {
+    let steam_api_key = api_key()?;
+    let url = format!(
+        "{}/ISteamUser/GetPlayerSummaries/v0002/",
+        AppSettings::get().services.steam_api_url
+    );
+    let steam_ids = steam_ids
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let summaries: PlayerSummariesResponse = metrics::track_external("steam", async {
+        CLIENT
+            .get(url)
+            .query(&[("key", steam_api_key), ("steamids", steam_ids.as_str())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    })
+    .await?;
+    Ok(summaries.response.players)
+}
+
+// Returns the SteamID64 behind a custom profile URL, or None when nobody uses it.
+pub async fn resolve_vanity_url(vanity_url: &str) -> ServiceResult<Option<u64>> {
+    let steam_api_key = api_key()?;
+    let url = format!(
+        "{}/ISteamUser/ResolveVanityURL/v0001/",
+        AppSettings::get().services.steam_api_url
+    );
+    let resolved: ResolveVanityUrlResponse = metrics::track_external("steam", async {
+        CLIENT
+            .get(url)
+            .query(&[("key", steam_api_key), ("vanityurl", vanity_url)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    })
+    .await?;
+
+    if resolved.response.success != VANITY_URL_RESOLVED {
+        return Ok(None);
+    }
+    Ok(resolved
+        .response
+        .steamid
+        .and_then(|steam_id| steam_id.parse().ok()))
+}
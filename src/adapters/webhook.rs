@@ -0,0 +1,42 @@
+use std::{sync::LazyLock, time::Duration};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("Failed to build webhook HTTP client")
+});
+
+pub fn sign(secret: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+pub async fn deliver(
+    url: &str,
+    secret: &str,
+    delivery_id: u64,
+    event: &str,
+    payload: &str,
+) -> anyhow::Result<()> {
+    let response = CLIENT
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("X-Shion-Event", event)
+        .header("X-Shion-Delivery", delivery_id.to_string())
+        .header("X-Shion-Signature", sign(secret, payload))
+        .body(payload.to_string())
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Webhook endpoint responded with {}", response.status());
+    }
+
+    Ok(())
+}
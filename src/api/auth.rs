@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+
+use actix_web::{
+    Error, ResponseError,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::HeaderName,
+    middleware::Next,
+    web::Data,
+};
+
+use crate::{common::error::AppError, settings::AuthSettings};
+
+pub struct WriteApiKeys {
+    header: HeaderName,
+    keys: HashSet<String>,
+}
+
+impl WriteApiKeys {
+    pub fn new(settings: &AuthSettings) -> Self {
+        Self {
+            header: HeaderName::from_bytes(settings.api_key_header.as_bytes())
+                .expect("Invalid auth API key header"),
+            keys: settings
+                .write_api_keys
+                .iter()
+                .map(|key| key.expose().clone())
+                .collect(),
+        }
+    }
+
+    fn authorizes(&self, req: &ServiceRequest) -> bool {
+        req.headers()
+            .get(&self.header)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|key| self.keys.contains(key))
+    }
+}
+
+pub async fn require_write_api_key(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let authorized = req
+        .app_data::<Data<WriteApiKeys>>()
+        .is_some_and(|keys| keys.authorizes(&req));
+    if !authorized {
+        let err = AppError::Unauthorized;
+        return Ok(req
+            .into_response(err.error_response())
+            .map_into_right_body());
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
@@ -0,0 +1,48 @@
+use actix_cors::Cors;
+use actix_web::{
+    guard::GuardContext,
+    http::{Method, header::ACCESS_CONTROL_REQUEST_METHOD},
+};
+
+use crate::settings::CorsPolicySettings;
+
+pub fn policy(settings: &CorsPolicySettings) -> Cors {
+    let mut cors = Cors::default();
+
+    if settings.allowed_origins.iter().any(|origin| origin == "*") {
+        cors = cors.allow_any_origin().send_wildcard();
+    } else {
+        for origin in &settings.allowed_origins {
+            cors = cors.allowed_origin(origin);
+        }
+    }
+
+    if !settings.allowed_methods.is_empty() {
+        cors = cors.allowed_methods(settings.allowed_methods.iter().map(String::as_str));
+    }
+    if !settings.allowed_headers.is_empty() {
+        cors = cors.allowed_headers(settings.allowed_headers.iter().map(String::as_str));
+    }
+    if settings.supports_credentials {
+        cors = cors.supports_credentials();
+    }
+
+    cors.max_age(settings.max_age_secs)
+}
+
+fn is_write_method(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+// Preflight requests are routed by the method they ask permission for.
+pub fn is_write_request(ctx: &GuardContext) -> bool {
+    match ctx.head().method {
+        Method::OPTIONS => ctx
+            .head()
+            .headers()
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|method| Method::from_bytes(method.as_bytes()).ok())
+            .is_some_and(|method| is_write_method(&method)),
+        ref method => is_write_method(method),
+    }
+}
@@ -0,0 +1,39 @@
+use actix_web::{
+    HttpRequest, HttpResponse, get,
+    web::{self, Data},
+};
+use utoipa::OpenApi;
+
+use crate::{
+    api::http_cache::cached_json,
+    common::{
+        error::{ErrorResponse, ServiceResult},
+        state::State,
+    },
+    models::countries::{CountrySummary, Region},
+    usecases::players,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(fetch_countries), components(schemas(CountrySummary, Region)))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    tag = "countries",
+    responses(
+        (status = 200, description = "Player counts and average ratings per country", body = Vec<CountrySummary>),
+        (status = 304, description = "The countries have not changed since the given ETag"),
+        (status = 500, description = "unexpected", body = ErrorResponse),
+    )
+)]
+#[get("/")]
+async fn fetch_countries(req: HttpRequest, state: Data<State>) -> ServiceResult<HttpResponse> {
+    let countries = players::fetch_country_summaries(&state).await?;
+    cached_json(&req, &countries)
+}
+
+pub fn router(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/countries").service(fetch_countries);
+
+    conf.service(scope);
+}
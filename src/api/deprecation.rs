@@ -0,0 +1,16 @@
+use actix_web::middleware::DefaultHeaders;
+use chrono::{DateTime, Utc};
+
+pub fn deprecated(
+    deprecated_at: DateTime<Utc>,
+    sunset: DateTime<Utc>,
+    successor: &str,
+) -> DefaultHeaders {
+    DefaultHeaders::new()
+        .add(("Deprecation", format!("@{}", deprecated_at.timestamp())))
+        .add((
+            "Sunset",
+            sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        ))
+        .add(("Link", format!("<{successor}>; rel=\"successor-version\"")))
+}
@@ -0,0 +1,107 @@
+use actix_web::{
+    HttpResponse, get,
+    http::header::ContentType,
+    web::{self, Json},
+};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{OpenApi as OpenApiDocument, RefOr, schema::Schema},
+};
+
+use crate::{
+    api,
+    common::error::{AppError, ErrorResponse},
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Shion", description = "Ratings and match history for AG servers."),
+    nest(
+        (path = "/health", api = api::health::ApiDoc),
+        (path = "/v1/countries", api = api::countries::ApiDoc),
+        (path = "/v1/events", api = api::events::ApiDoc),
+        (path = "/v1/match_details", api = api::match_details::ApiDoc),
+        (path = "/v1/matches", api = api::matches::ApiDoc),
+        (path = "/v1/players", api = api::players::ApiDoc),
+    ),
+    components(schemas(ErrorResponse)),
+    modifiers(&ErrorCodes)
+)]
+pub struct ApiDoc;
+
+struct ErrorCodes;
+
+impl Modify for ErrorCodes {
+    fn modify(&self, openapi: &mut OpenApiDocument) {
+        let mut description = String::from(
+            "Error returned by every endpoint on failure.\n\n| Code | HTTP status | Message |\n|---|---|---|\n",
+        );
+        for error in AppError::ALL {
+            description.push_str(&format!(
+                "| `{}` | {} | {} |\n",
+                error.code(),
+                error.http_status_code().as_u16(),
+                error.message()
+            ));
+        }
+
+        let schema = openapi
+            .components
+            .as_mut()
+            .and_then(|components| components.schemas.get_mut("ErrorResponse"));
+        if let Some(RefOr::T(Schema::Object(object))) = schema {
+            object.description = Some(description);
+        }
+    }
+}
+
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html>
+  <head>
+    <title>Shion API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+  </head>
+  <body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+    <script>
+      window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+    </script>
+  </body>
+</html>"##;
+
+const REDOC: &str = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>Shion API</title>
+  </head>
+  <body>
+    <redoc spec-url="/openapi.json"></redoc>
+    <script src="https://cdn.redoc.ly/redoc/latest/bundles/redoc.standalone.js"></script>
+  </body>
+</html>"#;
+
+#[get("/openapi.json")]
+async fn fetch_openapi() -> Json<OpenApiDocument> {
+    Json(ApiDoc::openapi())
+}
+
+#[get("/docs")]
+async fn swagger_ui() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(SWAGGER_UI)
+}
+
+#[get("/redoc")]
+async fn redoc() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(REDOC)
+}
+
+pub fn router(conf: &mut web::ServiceConfig) {
+    conf.service(fetch_openapi)
+        .service(swagger_ui)
+        .service(redoc);
+}
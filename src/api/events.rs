@@ -0,0 +1,70 @@
+use actix_web::{
+    HttpResponse, get,
+    web::{self, Bytes, Data},
+};
+use futures_util::stream;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::OpenApi;
+
+use crate::common::{events::Event, state::State};
+
+#[derive(OpenApi)]
+#[openapi(paths(stream_matches), components(schemas(Event)))]
+pub struct ApiDoc;
+
+fn format_event(event: &Event) -> Bytes {
+    let data = serde_json::to_string(event).unwrap_or_default();
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event.name(), data))
+}
+
+#[utoipa::path(
+    tag = "events",
+    responses(
+        (status = 200, description = "Server-sent events stream of processed matches", body = Event, content_type = "text/event-stream"),
+    )
+)]
+#[get("/matches")]
+async fn stream_matches(state: Data<State>) -> HttpResponse {
+    let receiver = state.events.subscribe();
+    let shutdown = state.shutdown.clone();
+
+    // Streams end on shutdown, otherwise open connections would hold the server
+    // until the shutdown timeout.
+    let events = stream::unfold(
+        (receiver, shutdown),
+        |(mut receiver, shutdown)| async move {
+            loop {
+                let received = tokio::select! {
+                    _ = shutdown.triggered() => return None,
+                    received = receiver.recv() => received,
+                };
+                match received {
+                    Ok(event @ Event::MatchProcessed(_)) => {
+                        return Some((
+                            Ok::<_, actix_web::Error>(format_event(&event)),
+                            (receiver, shutdown),
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Live match feed subscriber lagged behind, skipped {skipped} events."
+                        );
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events)
+}
+
+pub fn router(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/events").service(stream_matches);
+
+    conf.service(scope);
+}
@@ -0,0 +1,43 @@
+use actix_web::{
+    HttpResponse, get,
+    web::{self, Data},
+};
+use serde_json::json;
+use utoipa::OpenApi;
+
+use crate::{common::state::State, models::health::Readiness, usecases::health};
+
+#[derive(OpenApi)]
+#[openapi(paths(live, ready), components(schemas(Readiness)))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    tag = "health",
+    responses((status = 200, description = "The process is running")),
+)]
+#[get("/live")]
+async fn live() -> HttpResponse {
+    HttpResponse::Ok().json(json!({ "status": "ok" }))
+}
+
+#[utoipa::path(
+    tag = "health",
+    responses(
+        (status = 200, description = "The database is reachable and all migrations are applied", body = Readiness),
+        (status = 503, description = "A dependency is unavailable or the server is shutting down", body = Readiness),
+    )
+)]
+#[get("/ready")]
+async fn ready(state: Data<State>) -> HttpResponse {
+    let readiness = health::check_readiness(&state, state.shutdown.is_triggered()).await;
+    match readiness.ready {
+        true => HttpResponse::Ok().json(readiness),
+        false => HttpResponse::ServiceUnavailable().json(readiness),
+    }
+}
+
+pub fn router(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/health").service(live).service(ready);
+
+    conf.service(scope);
+}
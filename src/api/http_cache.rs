@@ -0,0 +1,37 @@
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse,
+    http::header::{CacheControl, CacheDirective, ContentType, ETag, EntityTag, IfNoneMatch},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{common::error::ServiceResult, settings::AppSettings};
+
+// Serializes `value` with an ETag so clients can revalidate with If-None-Match.
+pub fn cached_json<T: Serialize>(req: &HttpRequest, value: &T) -> ServiceResult<HttpResponse> {
+    let body = serde_json::to_vec(value)?;
+    let digest = Sha256::digest(&body);
+    let etag = EntityTag::new_strong(hex::encode(&digest[..16]));
+    let cache_control = CacheControl(vec![
+        CacheDirective::Public,
+        CacheDirective::MaxAge(AppSettings::get().cache.http_max_age_secs),
+    ]);
+
+    let not_modified = match req.get_header::<IfNoneMatch>() {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        None => false,
+    };
+    if not_modified {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .insert_header(cache_control)
+            .finish());
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header(ETag(etag))
+        .insert_header(cache_control)
+        .content_type(ContentType::json())
+        .body(body))
+}
@@ -3,36 +3,72 @@ use actix_web::{
     web::{self, Data, Json},
 };
 use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+use validator::Validate;
 
 use crate::{
-    common::{error::ServiceResponse, state::State},
+    common::{
+        error::{AppError, ErrorResponse, ServiceResponse},
+        state::State,
+        validation::{validate_all, validate_model},
+    },
     usecases,
 };
 
-#[derive(Deserialize, Clone)]
+#[derive(OpenApi)]
+#[openapi(paths(create_match_details), components(schemas(RequestBody)))]
+pub struct ApiDoc;
+
+#[derive(Deserialize, Clone, ToSchema, Validate)]
 pub struct RequestBody {
+    #[schema(example = "STEAM_0:1:12345678")]
+    #[validate(length(min = 1, max = 128, message = "must be between 1 and 128 characters"))]
     pub steam_id: String,
+    #[schema(example = "Hamlet")]
+    #[validate(length(min = 1, max = 64, message = "must be between 1 and 64 characters"))]
+    pub nickname: Option<String>,
+    #[validate(range(min = 1, message = "must be a valid match ID"))]
     pub match_id: u64,
+    #[validate(range(min = 0, message = "must not be negative"))]
     pub frags: i16,
+    #[validate(range(min = 0, message = "must not be negative"))]
     pub deaths: i16,
+    #[validate(range(max = 1000, message = "must be at most 1000"))]
     pub average_ping: u16,
     pub damage_dealt: u16,
     pub damage_taken: u16,
+    #[schema(example = "blue")]
+    #[validate(custom(function = "validate_model"))]
     pub model: String,
 }
 
+#[utoipa::path(
+    tag = "match_details",
+    request_body = Vec<RequestBody>,
+    responses(
+        (status = 200, description = "Details were stored and the match was processed"),
+        (status = 400, description = "validation_failed, invalid_model, uneven_teams, player_steamid_invalid", body = ErrorResponse),
+        (status = 404, description = "player_steamid_does_not_exist", body = ErrorResponse),
+        (status = 500, description = "unexpected", body = ErrorResponse),
+        (status = 503, description = "shutting_down", body = ErrorResponse),
+    )
+)]
 #[post("/")]
 async fn create_match_details(
     state: Data<State>,
     details: Json<Vec<RequestBody>>,
 ) -> ServiceResponse<()> {
+    let Some(_in_flight) = state.shutdown.track() else {
+        return Err(AppError::ShuttingDown);
+    };
+    validate_all(&details)?;
     usecases::match_details::create_match_details(&state, &details).await?;
-    usecases::match_details::process_match(&state, details[0].match_id).await?;
+    usecases::match_details::process_match(&state, details[0].match_id, false).await?;
 
     Ok(Json(()))
 }
 
-pub fn router(conf: &mut web::ServiceConfig) {
+pub fn write_router(conf: &mut web::ServiceConfig) {
     let scope = web::scope("/match_details").service(create_match_details);
 
     conf.service(scope);
@@ -1,29 +1,127 @@
 use actix_web::{
-    get, post,
-    web::{self, Data, Json},
+    HttpRequest, HttpResponse, get, post,
+    web::{self, Data, Json, Query},
 };
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use validator::{Validate, ValidationError};
 
 use crate::{
-    common::{error::ServiceResponse, state::State},
+    api::http_cache::cached_json,
+    common::{
+        error::{AppError, ErrorResponse, ServiceResponse, ServiceResult},
+        state::State,
+        validation::validate,
+    },
     models::matches::{Match, MatchExtended},
-    usecases::matches,
+    usecases::matches::{self, MatchFilters},
 };
 
-#[derive(Deserialize)]
+#[derive(OpenApi)]
+#[openapi(
+    paths(fetch_matches, fetch_match, create_match),
+    components(schemas(RequestBody, Match, MatchExtended))
+)]
+pub struct ApiDoc;
+
+#[derive(Deserialize, ToSchema, Validate)]
 pub struct RequestBody {
+    #[validate(length(min = 1, max = 21, message = "must be between 1 and 21 characters"))]
     pub server_ip: String,
+    #[validate(length(min = 1, max = 64, message = "must be between 1 and 64 characters"))]
     pub map_name: String,
 }
 
+#[derive(Deserialize, IntoParams, Validate)]
+#[validate(schema(function = "validate_date_range"))]
+pub struct RequestQuery {
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    page: Option<u32>,
+    #[validate(range(min = 1, max = 50, message = "must be between 1 and 50"))]
+    limit: Option<u32>,
+    map_name: Option<String>,
+    server_ip: Option<String>,
+    match_type: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    min_team_size: Option<u32>,
+}
+
+fn validate_date_range(query: &RequestQuery) -> Result<(), ValidationError> {
+    match (query.from, query.to) {
+        (Some(from), Some(to)) if from > to => Err(ValidationError::new("invalid_date_range")
+            .with_message("'from' must not be later than 'to'".into())),
+        _ => Ok(()),
+    }
+}
+
+#[utoipa::path(
+    tag = "matches",
+    params(RequestQuery),
+    responses(
+        (status = 200, description = "Recent matches, newest first", body = Vec<MatchExtended>),
+        (status = 400, description = "validation_failed", body = ErrorResponse),
+        (status = 500, description = "unexpected", body = ErrorResponse),
+    )
+)]
+#[get("/")]
+async fn fetch_matches(
+    state: Data<State>,
+    query: Query<RequestQuery>,
+) -> ServiceResponse<Vec<MatchExtended>> {
+    validate(&*query)?;
+    let page = query.page.unwrap_or(1);
+    let limit = query.limit.unwrap_or(10);
+    let filters = MatchFilters {
+        map_name: query.map_name.as_deref(),
+        server_ip: query.server_ip.as_deref(),
+        match_type: query.match_type.as_deref(),
+        from: query.from,
+        to: query.to,
+        min_team_size: query.min_team_size,
+    };
+
+    let recent_matches = matches::fetch_matches(&state, &filters, page, limit).await?;
+    Ok(Json(recent_matches))
+}
+
+#[utoipa::path(
+    tag = "matches",
+    params(("id" = u64, Path, description = "Match ID")),
+    responses(
+        (status = 200, description = "The match and its details", body = MatchExtended),
+        (status = 304, description = "The match has not changed since the given ETag"),
+        (status = 404, description = "match_not_found, match_detail_not_found", body = ErrorResponse),
+    )
+)]
 #[get("/{id}")]
-async fn fetch_match(state: Data<State>, path: web::Path<u64>) -> ServiceResponse<MatchExtended> {
+async fn fetch_match(
+    req: HttpRequest,
+    state: Data<State>,
+    path: web::Path<u64>,
+) -> ServiceResult<HttpResponse> {
     let a_match = matches::fetch_match(&state, path.into_inner()).await?;
-    Ok(Json(a_match))
+    cached_json(&req, &a_match)
 }
 
+#[utoipa::path(
+    tag = "matches",
+    request_body = RequestBody,
+    responses(
+        (status = 200, description = "The created match", body = Match),
+        (status = 400, description = "validation_failed", body = ErrorResponse),
+        (status = 503, description = "shutting_down", body = ErrorResponse),
+        (status = 500, description = "unexpected", body = ErrorResponse),
+    )
+)]
 #[post("/")]
 async fn create_match(state: Data<State>, body: Json<RequestBody>) -> ServiceResponse<Match> {
+    let Some(_in_flight) = state.shutdown.track() else {
+        return Err(AppError::ShuttingDown);
+    };
+    validate(&*body)?;
     let new_match = matches::create_match(
         &state,
         &body.server_ip,
@@ -35,8 +133,14 @@ async fn create_match(state: Data<State>, body: Json<RequestBody>) -> ServiceRes
 
 pub fn router(conf: &mut web::ServiceConfig) {
     let scope = web::scope("/matches")
-        .service(fetch_match)
-        .service(create_match);
+        .service(fetch_matches)
+        .service(fetch_match);
+
+    conf.service(scope);
+}
+
+pub fn write_router(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/matches").service(create_match);
 
     conf.service(scope);
 }
@@ -0,0 +1,54 @@
+use std::time::Instant;
+
+use actix_web::{
+    Error, HttpResponse,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    get,
+    middleware::Next,
+    web::{self, Data},
+};
+
+use crate::common::{
+    metrics::{self, HTTP_REQUEST_DURATION_SECONDS, HTTP_REQUESTS_TOTAL},
+    state::State,
+};
+
+pub async fn track_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let started_at = Instant::now();
+    let response = next.call(req).await?;
+
+    // Routing happens after app-level middleware, so the matched pattern is
+    // only known once the request has been handled.
+    let method = response.request().method().to_string();
+    let route = response
+        .request()
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string());
+    let status = response.status().as_u16().to_string();
+    let labels = [method.as_str(), route.as_str(), status.as_str()];
+    HTTP_REQUESTS_TOTAL.with_label_values(&labels).inc();
+    HTTP_REQUEST_DURATION_SECONDS
+        .with_label_values(&labels)
+        .observe(started_at.elapsed().as_secs_f64());
+
+    Ok(response)
+}
+
+#[get("/metrics")]
+async fn fetch_metrics(state: Data<State>) -> HttpResponse {
+    metrics::DB_POOL_CONNECTIONS.set(state.db.size() as i64);
+    metrics::DB_POOL_IDLE_CONNECTIONS.set(state.db.num_idle() as i64);
+    metrics::DB_POOL_MAX_CONNECTIONS.set(state.db.options().get_max_connections() as i64);
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics::gather())
+}
+
+pub fn router(conf: &mut web::ServiceConfig) {
+    conf.service(fetch_metrics);
+}
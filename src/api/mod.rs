@@ -1,41 +1,141 @@
+pub mod auth;
+pub mod cors;
+pub mod countries;
+pub mod deprecation;
+pub mod docs;
+pub mod events;
+pub mod health;
+pub mod http_cache;
 pub mod match_details;
 pub mod matches;
+pub mod metrics;
 pub mod players;
+pub mod rate_limit;
+pub mod request_id;
+pub mod v1;
 
 use std::net::Ipv4Addr;
 
-use actix_cors::Cors;
 use actix_web::{
     App, HttpRequest, HttpResponse, HttpServer,
     body::BoxBody,
+    guard,
     http::header::ContentType,
-    middleware::Logger,
-    web::{self, Data},
+    middleware::{DefaultHeaders, Logger, from_fn},
+    rt,
+    web::{self, Data, JsonConfig, PathConfig, QueryConfig},
 };
+use futures_util::future::join;
 
-use crate::{api, lifecycle, settings::AppSettings};
+use crate::{
+    api, api::deprecation::deprecated, common::validation::extractor_error_handler, enrichment,
+    lifecycle, settings::AppSettings, webhooks,
+};
+
+const LOG_FORMAT: &str =
+    r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T request_id=%{X-Request-Id}o"#;
 
 pub async fn serve(settings: &AppSettings) -> anyhow::Result<()> {
+    // Without a key every Steam lookup fails, which would look like an outage and
+    // leave every new player with a placeholder profile.
+    if settings.steam_api_key.is_none() {
+        anyhow::bail!("STEAM_API_KEY is required to serve the API");
+    }
     let state = lifecycle::initialize_state(settings).await?;
+    if settings.migrate_on_startup {
+        info!("Applying database migrations before startup...");
+        lifecycle::run_migrations(&state).await?;
+    }
+    lifecycle::ensure_schema_up_to_date(&state).await?;
+    let shutdown = state.shutdown.clone();
+    webhooks::spawn_dispatcher(state.clone());
+    enrichment::spawn_worker(state.clone());
+
+    let cors_settings = settings.cors.clone();
+    let rate_limiter = settings
+        .rate_limit
+        .enabled
+        .then(|| Data::new(api::rate_limit::RateLimiter::new(&settings.rate_limit)));
+    if settings.auth.write_api_keys.is_empty() {
+        warn!("No write API keys are configured, every write request will be refused.");
+    }
+    let write_api_keys = Data::new(api::auth::WriteApiKeys::new(&settings.auth));
+    let server = HttpServer::new(move || {
+        // Write resources only exist in this scope, which also answers their preflights.
+        let write_routes = web::scope("")
+            .guard(guard::fn_guard(api::cors::is_write_request))
+            .wrap(from_fn(api::auth::require_write_api_key))
+            .wrap(from_fn(api::rate_limit::rate_limit))
+            .wrap(api::cors::policy(&cors_settings.write))
+            .configure(write_routes);
+        let public_routes = web::scope("")
+            .wrap(from_fn(api::rate_limit::rate_limit))
+            .wrap(api::cors::policy(&cors_settings.public))
+            .configure(routes);
 
-    HttpServer::new(move || {
-        let cors = Cors::default().allow_any_origin().send_wildcard();
+        let mut app = App::new();
+        if let Some(rate_limiter) = &rate_limiter {
+            app = app.app_data(rate_limiter.clone());
+        }
 
-        App::new()
-            .wrap(Logger::default())
-            .wrap(cors)
+        app.wrap(from_fn(api::metrics::track_requests))
+            .wrap(from_fn(api::request_id::request_id))
+            .wrap(Logger::new(LOG_FORMAT))
             .app_data(Data::new(state.clone()))
-            .configure(api::match_details::router)
-            .configure(api::matches::router)
-            .configure(api::players::router)
-            .route("/", web::get().to(hello))
+            .app_data(write_api_keys.clone())
+            .app_data(JsonConfig::default().error_handler(extractor_error_handler("body")))
+            .app_data(QueryConfig::default().error_handler(extractor_error_handler("query")))
+            .app_data(PathConfig::default().error_handler(extractor_error_handler("path")))
+            .service(write_routes)
+            .service(public_routes)
     })
+    .disable_signals()
+    .shutdown_timeout(settings.shutdown_grace_period.as_secs())
     .bind((Ipv4Addr::UNSPECIFIED, settings.app_port))?
-    .run()
-    .await?;
+    .run();
+
+    let handle = server.handle();
+    let grace_period = settings.shutdown_grace_period;
+    rt::spawn(async move {
+        shutdown.triggered().await;
+        info!("Waiting up to {grace_period:?} for in-flight requests to finish...");
+        // Both waits share the grace period, so stopping never takes longer than
+        // the termination grace period it is configured to match.
+        let (drained, ()) = join(shutdown.drained(grace_period), handle.stop(true)).await;
+        if !drained {
+            warn!("Grace period elapsed with ingestion still in flight.");
+        }
+    });
+
+    server.await?;
+    info!("API server stopped.");
     Ok(())
 }
 
+fn routes(conf: &mut web::ServiceConfig) {
+    conf.configure(api::docs::router)
+        .configure(api::health::router)
+        .configure(api::metrics::router)
+        .service(web::scope(api::v1::PREFIX).configure(api::v1::router))
+        .route("/", web::get().to(hello))
+        .service(web::scope("").wrap(legacy()).configure(api::v1::router));
+}
+
+fn write_routes(conf: &mut web::ServiceConfig) {
+    conf.service(web::scope(api::v1::PREFIX).configure(api::v1::write_router))
+        .service(
+            web::scope("")
+                .wrap(legacy())
+                .configure(api::v1::write_router),
+        );
+}
+
+// Unversioned routes are kept for existing consumers until they move to /v1.
+fn legacy() -> DefaultHeaders {
+    let settings = &AppSettings::get().legacy_routes;
+    deprecated(settings.deprecated_at, settings.sunset_at, api::v1::PREFIX)
+}
+
 async fn hello(_req: HttpRequest) -> HttpResponse<BoxBody> {
     HttpResponse::Ok()
         .content_type(ContentType::plaintext())
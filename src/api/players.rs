@@ -1,58 +1,168 @@
 use std::net::IpAddr;
 
 use actix_web::{
-    get, post,
+    HttpRequest, HttpResponse, get, post,
     web::{self, Data, Json, Query},
 };
 use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use validator::Validate;
 
 use crate::{
-    common::{error::ServiceResponse, state::State},
+    api::http_cache::cached_json,
+    common::{
+        error::{AppError, ErrorResponse, ServiceResponse, ServiceResult},
+        state::State,
+        validation::validate,
+    },
     models::{
+        countries::Region,
         matches::MatchExtended,
-        players::{Player, PlayerHistory},
+        players::{LeaderboardPage, Player, PlayerHistory, Versus},
     },
     usecases::players,
 };
 
-#[derive(Deserialize)]
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        search_players,
+        fetch_leaderboard,
+        fetch_leaderboard_around,
+        fetch_player_rating_history,
+        fetch_player_matches,
+        fetch_player_versus,
+        fetch_player_by_steam_id,
+        fetch_player,
+        create_player
+    ),
+    components(schemas(RequestBody, Player, Region, LeaderboardPage, PlayerHistory, Versus))
+)]
+pub struct ApiDoc;
+
+#[derive(Deserialize, ToSchema, Validate)]
 pub struct RequestBody {
+    #[schema(example = "STEAM_0:1:12345678")]
+    #[validate(length(min = 1, max = 128, message = "must be between 1 and 128 characters"))]
     pub steam_id: String,
+    #[schema(example = "Hamlet")]
+    #[validate(length(min = 1, max = 64, message = "must be between 1 and 64 characters"))]
+    pub nickname: Option<String>,
+    #[schema(value_type = String, format = "ip")]
     pub ip_address: IpAddr,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams, Validate)]
 pub struct RequestQuery {
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    page: Option<u32>,
+    #[validate(range(min = 1, max = 50, message = "must be between 1 and 50"))]
+    limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams, Validate)]
+pub struct LeaderboardRequestQuery {
+    #[validate(range(min = 1, message = "must be at least 1"))]
     page: Option<u32>,
+    #[validate(range(min = 1, max = 50, message = "must be between 1 and 50"))]
     limit: Option<u32>,
+    #[param(example = "fi")]
+    #[validate(length(equal = 2, message = "must be a 2-letter country code"))]
+    country: Option<String>,
+    region: Option<Region>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams, Validate)]
+pub struct AroundRequestQuery {
+    #[validate(range(min = 1, max = 50, message = "must be between 1 and 50"))]
+    limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams, Validate)]
 pub struct SearchRequestQuery {
+    #[validate(length(min = 1, max = 64, message = "must be between 1 and 64 characters"))]
     value: String,
 }
 
+#[utoipa::path(
+    tag = "players",
+    params(SearchRequestQuery),
+    responses(
+        (status = 200, description = "Players whose Steam ID, current or past Steam name, or in-game nickname matches the value", body = Vec<Player>),
+        (status = 400, description = "validation_failed", body = ErrorResponse),
+        (status = 429, description = "rate_limited", body = ErrorResponse),
+        (status = 500, description = "unexpected", body = ErrorResponse),
+    )
+)]
 #[get("/search")]
 async fn search_players(
     state: Data<State>,
     query: Query<SearchRequestQuery>,
 ) -> ServiceResponse<Vec<Player>> {
+    validate(&*query)?;
     let players = players::search_players(&state, &query.value).await?;
     Ok(Json(players))
 }
 
+#[utoipa::path(
+    tag = "players",
+    params(LeaderboardRequestQuery),
+    responses(
+        (status = 200, description = "Players ordered by rating, optionally within a country or region", body = Vec<Player>),
+        (status = 304, description = "The leaderboard has not changed since the given ETag"),
+        (status = 400, description = "validation_failed", body = ErrorResponse),
+        (status = 500, description = "unexpected", body = ErrorResponse),
+    )
+)]
 #[get("/leaderboard")]
 async fn fetch_leaderboard(
+    req: HttpRequest,
     state: Data<State>,
-    query: Query<RequestQuery>,
-) -> ServiceResponse<Vec<Player>> {
+    query: Query<LeaderboardRequestQuery>,
+) -> ServiceResult<HttpResponse> {
+    validate(&*query)?;
     let page = query.page.unwrap_or(1);
     let limit = query.limit.unwrap_or(10);
+    let country = query.country.as_deref().map(str::to_lowercase);
+
+    let leaderboard =
+        players::fetch_leaderboard(&state, country.as_deref(), query.region, page, limit).await?;
+    cached_json(&req, &leaderboard)
+}
+
+#[utoipa::path(
+    tag = "players",
+    params(("id" = u64, Path, description = "Player ID"), AroundRequestQuery),
+    responses(
+        (status = 200, description = "Leaderboard page centred on the player", body = LeaderboardPage),
+        (status = 304, description = "The leaderboard has not changed since the given ETag"),
+        (status = 400, description = "validation_failed", body = ErrorResponse),
+        (status = 404, description = "player_not_found, player_not_ranked", body = ErrorResponse),
+        (status = 500, description = "unexpected", body = ErrorResponse),
+    )
+)]
+#[get("/leaderboard/around/{id}")]
+async fn fetch_leaderboard_around(
+    req: HttpRequest,
+    state: Data<State>,
+    path: web::Path<u64>,
+    query: Query<AroundRequestQuery>,
+) -> ServiceResult<HttpResponse> {
+    validate(&*query)?;
+    let limit = query.limit.unwrap_or(10);
 
-    let leaderboard = players::fetch_leaderboard(&state, page, limit).await?;
-    Ok(Json(leaderboard))
+    let page = players::fetch_leaderboard_around(&state, path.into_inner(), limit).await?;
+    cached_json(&req, &page)
 }
 
+#[utoipa::path(
+    tag = "players",
+    params(("id" = u64, Path, description = "Player ID")),
+    responses(
+        (status = 200, description = "Daily rating captures over the last 90 days", body = PlayerHistory),
+        (status = 500, description = "unexpected", body = ErrorResponse),
+    )
+)]
 #[get("/{id}/rating_history")]
 async fn fetch_player_rating_history(
     state: Data<State>,
@@ -62,12 +172,22 @@ async fn fetch_player_rating_history(
     Ok(Json(history))
 }
 
+#[utoipa::path(
+    tag = "players",
+    params(("id" = u64, Path, description = "Player ID"), RequestQuery),
+    responses(
+        (status = 200, description = "Matches played by the player, newest first", body = Vec<MatchExtended>),
+        (status = 400, description = "validation_failed", body = ErrorResponse),
+        (status = 404, description = "player_matches_not_found", body = ErrorResponse),
+    )
+)]
 #[get("/{id}/matches")]
 async fn fetch_player_matches(
     state: Data<State>,
     path: web::Path<u64>,
     query: Query<RequestQuery>,
 ) -> ServiceResponse<Vec<MatchExtended>> {
+    validate(&*query)?;
     let page = query.page.unwrap_or(1);
     let limit = query.limit.unwrap_or(10);
 
@@ -76,15 +196,88 @@ async fn fetch_player_matches(
     Ok(Json(player_matches))
 }
 
+#[utoipa::path(
+    tag = "players",
+    params(
+        ("id" = u64, Path, description = "Player ID"),
+        ("opponent_id" = u64, Path, description = "Opponent player ID"),
+    ),
+    responses(
+        (status = 200, description = "Head-to-head record of the two players", body = Versus),
+        (status = 404, description = "player_not_found", body = ErrorResponse),
+    )
+)]
+#[get("/{id}/versus/{opponent_id}")]
+async fn fetch_player_versus(
+    state: Data<State>,
+    path: web::Path<(u64, u64)>,
+) -> ServiceResponse<Versus> {
+    let (id, opponent_id) = path.into_inner();
+    let versus = players::fetch_versus(&state, id, opponent_id).await?;
+    Ok(Json(versus))
+}
+
+#[utoipa::path(
+    tag = "players",
+    params(("steam_id" = String, Path, description = "Steam2, Steam3, SteamID64 or steamcommunity.com profile URL")),
+    responses(
+        (status = 200, description = "The player and their stats", body = Player),
+        (status = 400, description = "player_steamid_invalid", body = ErrorResponse),
+        (status = 404, description = "player_not_found, player_steamid_does_not_exist", body = ErrorResponse),
+    )
+)]
+#[get("/steam/{steam_id:.*}")]
+async fn fetch_player_by_steam_id(
+    req: HttpRequest,
+    state: Data<State>,
+    path: web::Path<String>,
+) -> ServiceResult<HttpResponse> {
+    let player = players::fetch_player_by_steam_id(&state, &path).await?;
+    cached_json(&req, &player)
+}
+
+#[utoipa::path(
+    tag = "players",
+    params(("id" = u64, Path, description = "Player ID")),
+    responses(
+        (status = 200, description = "The player and their stats", body = Player),
+        (status = 304, description = "The player has not changed since the given ETag"),
+        (status = 404, description = "player_not_found", body = ErrorResponse),
+    )
+)]
 #[get("/{id}")]
-async fn fetch_player(state: Data<State>, path: web::Path<u64>) -> ServiceResponse<Player> {
+async fn fetch_player(
+    req: HttpRequest,
+    state: Data<State>,
+    path: web::Path<u64>,
+) -> ServiceResult<HttpResponse> {
     let player = players::fetch_player(&state, path.into_inner()).await?;
-    Ok(Json(player))
+    cached_json(&req, &player)
 }
 
+#[utoipa::path(
+    tag = "players",
+    request_body = RequestBody,
+    responses(
+        (status = 200, description = "The existing or newly created player", body = Player),
+        (status = 400, description = "validation_failed, player_steamid_invalid", body = ErrorResponse),
+        (status = 404, description = "player_steamid_does_not_exist", body = ErrorResponse),
+        (status = 503, description = "shutting_down", body = ErrorResponse),
+    )
+)]
 #[post("/")]
 async fn create_player(state: Data<State>, body: Json<RequestBody>) -> ServiceResponse<Player> {
-    let player = players::create_player(&state, &body.steam_id, body.ip_address).await?;
+    let Some(_in_flight) = state.shutdown.track() else {
+        return Err(AppError::ShuttingDown);
+    };
+    validate(&*body)?;
+    let player = players::create_player(
+        &state,
+        &body.steam_id,
+        body.nickname.as_deref(),
+        body.ip_address,
+    )
+    .await?;
     Ok(Json(player))
 }
 
@@ -92,10 +285,18 @@ pub fn router(conf: &mut web::ServiceConfig) {
     let scope = web::scope("/players")
         .service(search_players)
         .service(fetch_leaderboard)
+        .service(fetch_leaderboard_around)
         .service(fetch_player_rating_history)
         .service(fetch_player_matches)
-        .service(fetch_player)
-        .service(create_player);
+        .service(fetch_player_versus)
+        .service(fetch_player_by_steam_id)
+        .service(fetch_player);
+
+    conf.service(scope);
+}
+
+pub fn write_router(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/players").service(create_player);
 
     conf.service(scope);
 }
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use actix_web::{
+    Error, ResponseError,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{Method, header::HeaderName},
+    middleware::Next,
+    web::Data,
+};
+
+use crate::{
+    common::{error::AppError, rate_limit::TokenBucket},
+    settings::RateLimitSettings,
+};
+
+pub struct RateLimiter {
+    trust_proxy_headers: bool,
+    api_key_header: HeaderName,
+    api_keys: HashSet<String>,
+    read: TokenBucket,
+    search: TokenBucket,
+    write: TokenBucket,
+}
+
+impl RateLimiter {
+    pub fn new(settings: &RateLimitSettings) -> Self {
+        Self {
+            trust_proxy_headers: settings.trust_proxy_headers,
+            api_key_header: HeaderName::from_bytes(settings.api_key_header.as_bytes())
+                .expect("Invalid rate limit API key header"),
+            api_keys: settings
+                .api_keys
+                .iter()
+                .map(|key| key.expose().clone())
+                .collect(),
+            read: TokenBucket::new(settings.read),
+            search: TokenBucket::new(settings.search),
+            write: TokenBucket::new(settings.write),
+        }
+    }
+
+    fn bucket(&self, req: &ServiceRequest) -> Option<&TokenBucket> {
+        let path = req.path();
+        if path.starts_with("/health") || path == "/metrics" {
+            return None;
+        }
+
+        match *req.method() {
+            Method::GET | Method::HEAD | Method::OPTIONS if path.ends_with("/players/search") => {
+                Some(&self.search)
+            }
+            Method::GET | Method::HEAD | Method::OPTIONS => Some(&self.read),
+            _ => Some(&self.write),
+        }
+    }
+
+    // Only known API keys get their own bucket, otherwise rotating the header
+    // would be enough to get around the limit.
+    fn client_key(&self, req: &ServiceRequest) -> String {
+        if let Some(api_key) = req
+            .headers()
+            .get(&self.api_key_header)
+            .and_then(|value| value.to_str().ok())
+            .filter(|api_key| self.api_keys.contains(*api_key))
+        {
+            return format!("key:{api_key}");
+        }
+
+        let connection_info = req.connection_info();
+        let ip = match self.trust_proxy_headers {
+            true => connection_info.realip_remote_addr(),
+            false => connection_info.peer_addr(),
+        };
+        format!("ip:{}", ip.unwrap_or("unknown"))
+    }
+}
+
+pub async fn rate_limit(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if let Some(limiter) = req.app_data::<Data<RateLimiter>>()
+        && let Some(bucket) = limiter.bucket(&req)
+        && let Err(retry_after) = bucket.acquire(&limiter.client_key(&req))
+    {
+        // Answered here rather than returned as an error, so the response still
+        // passes through the CORS middleware and browsers can read it.
+        let err = AppError::RateLimited(retry_after.as_secs_f64().ceil() as u64);
+        return Ok(req
+            .into_response(err.error_response())
+            .map_into_right_body());
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
@@ -0,0 +1,18 @@
+use actix_web::web;
+
+use crate::api;
+
+pub const PREFIX: &str = "/v1";
+
+pub fn router(conf: &mut web::ServiceConfig) {
+    conf.configure(api::countries::router)
+        .configure(api::events::router)
+        .configure(api::matches::router)
+        .configure(api::players::router);
+}
+
+pub fn write_router(conf: &mut web::ServiceConfig) {
+    conf.configure(api::match_details::write_router)
+        .configure(api::matches::write_router)
+        .configure(api::players::write_router);
+}
@@ -1,23 +1,64 @@
 use crate::{
     adapters::agdb_api,
+    cli::BackfillArgs,
     lifecycle,
     repositories::{players, stats},
     settings::AppSettings,
 };
 
+const STATS_JOB_NAME: &str = "backfill_stats";
+const COUNTRIES_JOB_NAME: &str = "backfill_countries";
+
 // XXX: This is temporary to backfill stats for existing players that were created before we had the stats table.
 // Once all players have stats we can remove this.
-pub async fn backfill_stats(settings: &AppSettings) -> anyhow::Result<()> {
+pub async fn backfill_stats(settings: &AppSettings, args: BackfillArgs) -> anyhow::Result<()> {
     info!("Starting stats backfill...");
 
     let state = lifecycle::initialize_state(settings).await?;
+    let dry_run = args.batch.dry_run;
+
+    let mut last_player_id =
+        lifecycle::resume_point(&state, STATS_JOB_NAME, args.from_player).await?;
+    loop {
+        let batch =
+            players::fetch_batch(&state, last_player_id.unwrap_or(0), args.batch.batch_size)
+                .await?;
+        if batch.is_empty() {
+            break;
+        }
+
+        for player in batch {
+            if state.shutdown.is_triggered() {
+                if !dry_run {
+                    lifecycle::checkpoint(&state, STATS_JOB_NAME, last_player_id, None).await?;
+                }
+                return Ok(());
+            }
+            last_player_id = Some(player.id);
 
-    let players = players::fetch_all(&state).await?;
+            if dry_run {
+                info!("Would backfill stats for player ID: {}", player.id);
+                continue;
+            }
 
-    for player in players {
-        info!("Backfilling stats for player ID: {}", player.id);
+            info!("Backfilling stats for player ID: {}", player.id);
 
-        stats::update_stats(&state, player.id, 1000.0, 333.33333, 0, 0, 0, 0).await?;
+            stats::update_stats(
+                &state,
+                player.id,
+                settings.rating.initial_rating,
+                settings.rating.initial_uncertainty,
+                0,
+                0,
+                0,
+                0,
+            )
+            .await?;
+        }
+    }
+
+    if !dry_run {
+        lifecycle::complete(&state, STATS_JOB_NAME).await?;
     }
 
     info!("Stats backfill completed.");
@@ -27,20 +68,46 @@ pub async fn backfill_stats(settings: &AppSettings) -> anyhow::Result<()> {
 
 // XXX: This is temporary to backfill countries for existing players that were created before we had the country field in the players table.
 // Once all players have a country we can remove this.
-pub async fn backfill_countries(settings: &AppSettings) -> anyhow::Result<()> {
+pub async fn backfill_countries(settings: &AppSettings, args: BackfillArgs) -> anyhow::Result<()> {
     info!("Starting countries backfill...");
 
     let state = lifecycle::initialize_state(settings).await?;
+    let dry_run = args.batch.dry_run;
+
+    let mut last_player_id =
+        lifecycle::resume_point(&state, COUNTRIES_JOB_NAME, args.from_player).await?;
+    loop {
+        let batch = players::fetch_batch_with_unknown_country(
+            &state,
+            last_player_id.unwrap_or(0),
+            args.batch.batch_size,
+        )
+        .await?;
+        if batch.is_empty() {
+            break;
+        }
 
-    let players = players::fetch_all_with_unknown_country(&state).await?;
+        for player in batch {
+            if state.shutdown.is_triggered() {
+                if !dry_run {
+                    lifecycle::checkpoint(&state, COUNTRIES_JOB_NAME, last_player_id, None).await?;
+                }
+                return Ok(());
+            }
+            last_player_id = Some(player.id);
 
-    for player in players {
-        if player.country == "xx" {
             info!(
                 "Requesting info from AGDB API for player ID: {}",
                 player.steam_id
             );
             match agdb_api::fetch_player_info(player.steam_id).await {
+                Ok(agdb_player) if dry_run => {
+                    info!(
+                        "Would backfill country for player ID: {} to {}",
+                        agdb_player.steam_id,
+                        agdb_player.country.to_lowercase()
+                    );
+                }
                 Ok(agdb_player) => {
                     info!(
                         "Backfilling country for player ID: {} to {}",
@@ -57,6 +124,10 @@ pub async fn backfill_countries(settings: &AppSettings) -> anyhow::Result<()> {
         }
     }
 
+    if !dry_run {
+        lifecycle::complete(&state, COUNTRIES_JOB_NAME).await?;
+    }
+
     info!("Countries backfill completed.");
 
     Ok(())
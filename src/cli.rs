@@ -0,0 +1,115 @@
+use anyhow::bail;
+use clap::{Args, Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(version, about = "AG rating service")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Serve the HTTP API
+    Serve,
+    /// Run the Discord bot
+    DiscordBot,
+    /// Apply pending database migrations
+    Migrate,
+    /// Recompute ratings and stats from the stored matches
+    Reprocess(ReprocessArgs),
+    /// Delete matches with uneven teams or very low frags
+    FixMatches(FixMatchesArgs),
+    /// Periodically refresh player names and avatars from Steam
+    RefreshProfiles(RefreshProfilesArgs),
+    /// Backfill player data created before newer fields existed
+    #[command(subcommand)]
+    Backfill(BackfillCommand),
+}
+
+#[derive(Subcommand)]
+pub enum BackfillCommand {
+    /// Give every player default stats
+    Stats(BackfillArgs),
+    /// Look up countries for players with an unknown country
+    Countries(BackfillArgs),
+}
+
+#[derive(Args, Clone, Copy)]
+pub struct BatchArgs {
+    /// Log what would change without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Number of rows loaded from the database at a time
+    #[arg(long, default_value_t = 500, value_parser = clap::value_parser!(u32).range(1..))]
+    pub batch_size: u32,
+}
+
+#[derive(Args, Clone, Copy, Default)]
+pub struct ReprocessArgs {
+    /// Resume at this match ID, which must directly follow the saved checkpoint
+    #[arg(long)]
+    pub from_match: Option<u64>,
+    #[command(flatten)]
+    pub batch: BatchArgs,
+}
+
+#[derive(Args, Clone, Copy, Default)]
+pub struct FixMatchesArgs {
+    /// Start at this match ID instead of the saved checkpoint
+    #[arg(long)]
+    pub from_match: Option<u64>,
+    #[command(flatten)]
+    pub batch: BatchArgs,
+}
+
+#[derive(Args, Clone, Copy, Default)]
+pub struct BackfillArgs {
+    /// Start at this player ID instead of the saved checkpoint
+    #[arg(long)]
+    pub from_player: Option<u64>,
+    #[command(flatten)]
+    pub batch: BatchArgs,
+}
+
+#[derive(Args, Clone, Copy, Default)]
+pub struct RefreshProfilesArgs {
+    /// Log what would change without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Refresh every profile once and exit instead of repeating
+    #[arg(long)]
+    pub once: bool,
+}
+
+impl Default for BatchArgs {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            batch_size: 500,
+        }
+    }
+}
+
+impl Command {
+    // Deployments predating the CLI select the mode through APP_COMPONENT.
+    pub fn from_app_component(app_component: Option<&str>) -> anyhow::Result<Self> {
+        let command = match app_component {
+            Some("api") => Command::Serve,
+            Some("discord_bot") => Command::DiscordBot,
+            Some("migrate") => Command::Migrate,
+            Some("processor") => Command::Reprocess(ReprocessArgs::default()),
+            Some("fix_matches") => Command::FixMatches(FixMatchesArgs::default()),
+            Some("profile_refresher") => Command::RefreshProfiles(RefreshProfilesArgs::default()),
+            Some("backfill_stats") => {
+                Command::Backfill(BackfillCommand::Stats(BackfillArgs::default()))
+            }
+            Some("backfill_countries") => {
+                Command::Backfill(BackfillCommand::Countries(BackfillArgs::default()))
+            }
+            Some(other) => bail!("Unknown app component: {other}"),
+            None => bail!("No command given and APP_COMPONENT is not set, see --help"),
+        };
+        Ok(command)
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    common::error::ServiceResult,
+    settings::{CacheBackend, CacheSettings},
+};
+
+#[derive(Clone)]
+pub struct Cache {
+    store: Arc<Store>,
+    ttl: Duration,
+}
+
+enum Store {
+    Disabled,
+    Memory(MemoryStore),
+    #[cfg(feature = "redis")]
+    Redis(RedisStore),
+}
+
+impl Cache {
+    pub async fn new(settings: &CacheSettings) -> anyhow::Result<Self> {
+        let store = match settings.backend {
+            CacheBackend::None => Store::Disabled,
+            CacheBackend::Memory => Store::Memory(MemoryStore::new(settings.max_entries)),
+            #[cfg(feature = "redis")]
+            CacheBackend::Redis => {
+                let url = settings
+                    .redis_url
+                    .as_ref()
+                    .map(|url| url.expose().as_str())
+                    .unwrap_or_default();
+                Store::Redis(RedisStore::connect(url).await?)
+            }
+            #[cfg(not(feature = "redis"))]
+            CacheBackend::Redis => anyhow::bail!("The redis cache backend is not compiled in"),
+        };
+
+        Ok(Self {
+            store: Arc::new(store),
+            ttl: settings.ttl,
+        })
+    }
+
+    // Only successful results are cached, errors always go back to the loader.
+    pub async fn get_or_load<T, F, Fut>(&self, key: &str, load: F) -> ServiceResult<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = ServiceResult<T>>,
+    {
+        if let Some(cached) = self.get(key).await {
+            match serde_json::from_str(&cached) {
+                Ok(value) => return Ok(value),
+                Err(e) => warn!("Discarding unreadable cache entry {key}: {e}"),
+            }
+        }
+
+        let value = load().await?;
+        match serde_json::to_string(&value) {
+            Ok(serialized) => self.set(key, serialized).await,
+            Err(e) => warn!("Failed to serialize cache entry {key}: {e}"),
+        }
+        Ok(value)
+    }
+
+    pub async fn invalidate_all(&self) {
+        match self.store.as_ref() {
+            Store::Disabled => {}
+            Store::Memory(store) => store.clear(),
+            #[cfg(feature = "redis")]
+            Store::Redis(store) => {
+                if let Err(e) = store.clear().await {
+                    warn!("Failed to invalidate the Redis cache: {e}");
+                }
+            }
+        }
+    }
+
+    async fn get(&self, key: &str) -> Option<String> {
+        match self.store.as_ref() {
+            Store::Disabled => None,
+            Store::Memory(store) => store.get(key),
+            #[cfg(feature = "redis")]
+            Store::Redis(store) => store.get(key).await.unwrap_or_else(|e| {
+                warn!("Failed to read {key} from the Redis cache: {e}");
+                None
+            }),
+        }
+    }
+
+    async fn set(&self, key: &str, value: String) {
+        match self.store.as_ref() {
+            Store::Disabled => {}
+            Store::Memory(store) => store.set(key, value, self.ttl),
+            #[cfg(feature = "redis")]
+            Store::Redis(store) => {
+                if let Err(e) = store.set(key, value, self.ttl).await {
+                    warn!("Failed to write {key} to the Redis cache: {e}");
+                }
+            }
+        }
+    }
+}
+
+struct MemoryStore {
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (Instant, String)>>,
+}
+
+impl MemoryStore {
+    fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(expires_at, _)| *expires_at > Instant::now())
+            .map(|(_, value)| value.clone())
+    }
+
+    fn set(&self, key: &str, value: String, ttl: Duration) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.max_entries {
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+        }
+        if entries.len() >= self.max_entries {
+            entries.clear();
+        }
+        entries.insert(key.to_string(), (now + ttl, value));
+    }
+
+    fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+// Invalidation bumps a generation counter instead of deleting keys, entries
+// from older generations are simply never read again and expire on their own.
+#[cfg(feature = "redis")]
+struct RedisStore {
+    connection: redis::aio::ConnectionManager,
+}
+
+#[cfg(feature = "redis")]
+impl RedisStore {
+    const KEY_PREFIX: &str = "shion:cache";
+    const GENERATION_KEY: &str = "shion:cache:generation";
+
+    async fn connect(url: &str) -> redis::RedisResult<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = redis::aio::ConnectionManager::new(client).await?;
+        Ok(Self { connection })
+    }
+
+    async fn versioned_key(&self, key: &str) -> redis::RedisResult<String> {
+        use redis::AsyncCommands;
+
+        let generation: Option<u64> = self.connection.clone().get(Self::GENERATION_KEY).await?;
+        Ok(format!(
+            "{}:{}:{key}",
+            Self::KEY_PREFIX,
+            generation.unwrap_or(0)
+        ))
+    }
+
+    async fn get(&self, key: &str) -> redis::RedisResult<Option<String>> {
+        use redis::AsyncCommands;
+
+        let key = self.versioned_key(key).await?;
+        self.connection.clone().get(key).await
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> redis::RedisResult<()> {
+        use redis::AsyncCommands;
+
+        let key = self.versioned_key(key).await?;
+        self.connection
+            .clone()
+            .set_ex(key, value, ttl.as_secs().max(1))
+            .await
+    }
+
+    async fn clear(&self) -> redis::RedisResult<()> {
+        use redis::AsyncCommands;
+
+        self.connection
+            .clone()
+            .incr::<_, _, ()>(Self::GENERATION_KEY, 1)
+            .await
+    }
+}
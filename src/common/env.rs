@@ -1,17 +1,73 @@
 use std::env;
-use std::error::Error;
+use std::fmt::Display;
 use std::str::FromStr;
+use std::time::Duration;
 
-pub trait FromEnv: Sized {
-    fn from_env(env_var: &str) -> anyhow::Result<Self>;
+// Applies environment variables on top of already loaded values, collecting
+// every parse failure instead of stopping at the first one.
+#[derive(Default)]
+pub struct EnvOverlay {
+    pub errors: Vec<String>,
 }
 
-impl<T: FromStr> FromEnv for T
-where
-    <T as FromStr>::Err: 'static + Error + Send + Sync,
-{
-    fn from_env(env_var: &str) -> anyhow::Result<Self> {
-        let value = env::var(env_var)?;
-        Ok(T::from_str(&value)?)
+impl EnvOverlay {
+    fn read(&mut self, env_var: &str) -> Option<String> {
+        match env::var(env_var) {
+            Ok(value) => Some(value),
+            Err(env::VarError::NotPresent) => None,
+            Err(e) => {
+                self.errors.push(format!("{env_var}: {e}"));
+                None
+            }
+        }
+    }
+
+    fn parse<T: FromStr>(&mut self, env_var: &str) -> Option<T>
+    where
+        T::Err: Display,
+    {
+        let value = self.read(env_var)?;
+        match T::from_str(value.trim()) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.errors.push(format!("{env_var}: {e}"));
+                None
+            }
+        }
+    }
+
+    pub fn set<T: FromStr>(&mut self, env_var: &str, target: &mut T)
+    where
+        T::Err: Display,
+    {
+        if let Some(value) = self.parse(env_var) {
+            *target = value;
+        }
+    }
+
+    pub fn set_optional<T: FromStr>(&mut self, env_var: &str, target: &mut Option<T>)
+    where
+        T::Err: Display,
+    {
+        if let Some(value) = self.parse(env_var) {
+            *target = Some(value);
+        }
+    }
+
+    pub fn set_secs(&mut self, env_var: &str, target: &mut Duration) {
+        if let Some(secs) = self.parse(env_var) {
+            *target = Duration::from_secs(secs);
+        }
+    }
+
+    pub fn set_list(&mut self, env_var: &str, target: &mut Vec<String>) {
+        if let Some(value) = self.read(env_var) {
+            *target = value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
     }
 }
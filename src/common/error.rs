@@ -1,7 +1,14 @@
-use actix_web::{HttpResponse, ResponseError, http::StatusCode, web::Json};
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::RETRY_AFTER},
+    web::Json,
+};
 use serde::Serialize;
 use std::fmt;
 use tracing::error;
+use utoipa::ToSchema;
+
+use crate::common::request_id;
 
 pub type ServiceResult<T> = Result<T, AppError>;
 pub type ServiceResponse<T> = ServiceResult<Json<T>>;
@@ -9,7 +16,11 @@ pub type ServiceResponse<T> = ServiceResult<Json<T>>;
 #[track_caller]
 pub fn unexpected<T, E: Into<anyhow::Error>>(e: E) -> ServiceResult<T> {
     let caller = std::panic::Location::caller();
-    error!("An unexpected error has occurred at {caller}: {}", e.into());
+    error!(
+        location = %caller,
+        cause = format!("{:#}", e.into()),
+        "An unexpected error has occurred"
+    );
     Err(AppError::Unexpected)
 }
 
@@ -18,17 +29,21 @@ pub enum AppError {
     Unexpected,
     Unauthorized,
     InternalServerError(&'static str),
+    ShuttingDown,
+    RateLimited(u64),
 
     PlayerNotFound,
     PlayerMatchesNotFound,
     PlayerSteamIDInvalid,
     PlayerSteamDoesNotExist,
+    PlayerNotRanked,
 
     MatchNotFound,
     MatchDetailNotFound,
 
     InvalidModel,
     UnevenTeams,
+    Validation(Vec<FieldError>),
 
     AGDBInvalidSteamID,
     AGDBPlayerNotFound,
@@ -43,6 +58,27 @@ impl<E: Into<anyhow::Error>> From<E> for AppError {
 }
 
 impl AppError {
+    pub const ALL: &[AppError] = &[
+        AppError::Unexpected,
+        AppError::Unauthorized,
+        AppError::InternalServerError(""),
+        AppError::ShuttingDown,
+        AppError::RateLimited(0),
+        AppError::PlayerNotFound,
+        AppError::PlayerMatchesNotFound,
+        AppError::PlayerSteamIDInvalid,
+        AppError::PlayerSteamDoesNotExist,
+        AppError::PlayerNotRanked,
+        AppError::MatchNotFound,
+        AppError::MatchDetailNotFound,
+        AppError::InvalidModel,
+        AppError::UnevenTeams,
+        AppError::Validation(Vec::new()),
+        AppError::AGDBInvalidSteamID,
+        AppError::AGDBPlayerNotFound,
+        AppError::AGDBPartialData,
+    ];
+
     pub const fn as_str(&self) -> &str {
         self.code()
     }
@@ -52,17 +88,21 @@ impl AppError {
             AppError::Unexpected => "unexpected",
             AppError::Unauthorized => "unauthorized",
             AppError::InternalServerError(_) => "internal_server_error",
+            AppError::ShuttingDown => "shutting_down",
+            AppError::RateLimited(_) => "rate_limited",
 
             AppError::PlayerNotFound => "player_not_found",
             AppError::PlayerMatchesNotFound => "player_matches_not_found",
             AppError::PlayerSteamIDInvalid => "player_steamid_invalid",
             AppError::PlayerSteamDoesNotExist => "player_steamid_does_not_exist",
+            AppError::PlayerNotRanked => "player_not_ranked",
 
             AppError::MatchNotFound => "match_not_found",
             AppError::MatchDetailNotFound => "match_detail_not_found",
 
             AppError::InvalidModel => "invalid_model",
             AppError::UnevenTeams => "uneven_teams",
+            AppError::Validation(_) => "validation_failed",
 
             AppError::AGDBInvalidSteamID => "agdb_invalid_steamid",
             AppError::AGDBPlayerNotFound => "agdb_player_not_found",
@@ -75,17 +115,21 @@ impl AppError {
             AppError::Unexpected => "An unexpected error has occurred.",
             AppError::Unauthorized => "You are not authorized to perform this action.",
             AppError::InternalServerError(_) => "An internal server error has occurred.",
+            AppError::ShuttingDown => "The server is shutting down, please retry shortly.",
+            AppError::RateLimited(_) => "Too many requests, please retry after the given delay.",
 
             AppError::PlayerNotFound => "The specified player was not found.",
             AppError::PlayerMatchesNotFound => "No matches found for the specified player.",
             AppError::PlayerSteamIDInvalid => "The provided Steam ID is invalid.",
             AppError::PlayerSteamDoesNotExist => "The provided Steam ID does not exist.",
+            AppError::PlayerNotRanked => "The specified player has no rank on the leaderboard yet.",
 
             AppError::MatchNotFound => "The specified match was not found.",
             AppError::MatchDetailNotFound => "The specified match detail was not found.",
 
             AppError::InvalidModel => "Invalid model value. Valid values are 'blue' or 'red'.",
             AppError::UnevenTeams => "Team sizes do not match.",
+            AppError::Validation(_) => "The request contains invalid fields.",
 
             AppError::AGDBInvalidSteamID => "The provided Steam ID is invalid according to AGDB.",
             AppError::AGDBPlayerNotFound => "No player found in AGDB for the provided Steam ID.",
@@ -100,6 +144,7 @@ impl AppError {
             AppError::PlayerSteamIDInvalid
             | AppError::InvalidModel
             | AppError::UnevenTeams
+            | AppError::Validation(_)
             | AppError::AGDBInvalidSteamID
             | AppError::AGDBPartialData => StatusCode::BAD_REQUEST,
 
@@ -108,6 +153,7 @@ impl AppError {
             AppError::PlayerNotFound
             | AppError::PlayerMatchesNotFound
             | AppError::PlayerSteamDoesNotExist
+            | AppError::PlayerNotRanked
             | AppError::MatchNotFound
             | AppError::MatchDetailNotFound
             | AppError::AGDBPlayerNotFound => StatusCode::NOT_FOUND,
@@ -115,29 +161,55 @@ impl AppError {
             AppError::Unexpected | AppError::InternalServerError(_) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
+
+            AppError::ShuttingDown => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    pub fn details(&self) -> &[FieldError] {
+        match self {
+            AppError::Validation(details) => details,
+            _ => &[],
         }
     }
 
-    pub const fn response_parts(&self) -> (StatusCode, Json<ErrorResponse>) {
+    pub fn response_parts(&self) -> (StatusCode, Json<ErrorResponse>) {
         let status = self.http_status_code();
         let response = ErrorResponse {
             code: self.code(),
             message: self.message(),
+            details: self.details().to_vec(),
+            request_id: request_id::current(),
         };
         (status, Json(response))
     }
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub code: &'static str,
     pub message: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl ResponseError for AppError {
     fn error_response(&self) -> HttpResponse {
         let (status, body) = self.response_parts();
-        HttpResponse::build(status).json(body)
+        let mut response = HttpResponse::build(status);
+        if let AppError::RateLimited(retry_after_secs) = self {
+            response.insert_header((RETRY_AFTER, retry_after_secs.to_string()));
+        }
+        response.json(body)
     }
 }
 
@@ -0,0 +1,52 @@
+use serde::Serialize;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use utoipa::ToSchema;
+
+use crate::models::events::{LeaderboardLeaderChanged, MatchProcessed, PlayerCreated};
+
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Serialize, Clone, ToSchema)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum Event {
+    MatchProcessed(MatchProcessed),
+    LeaderboardLeaderChanged(LeaderboardLeaderChanged),
+    PlayerCreated(PlayerCreated),
+}
+
+impl Event {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Event::MatchProcessed(_) => "match_processed",
+            Event::LeaderboardLeaderChanged(_) => "leaderboard_leader_changed",
+            Event::PlayerCreated(_) => "player_created",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EventBus {
+    sender: Sender<Event>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: Event) {
+        // Sending only fails when nobody is listening, which is fine.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,148 @@
+use std::{future::Future, sync::LazyLock, time::Instant};
+
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(collector: T) -> T {
+    REGISTRY
+        .register(Box::new(collector.clone()))
+        .expect("Failed to register metric");
+    collector
+}
+
+pub static HTTP_REQUESTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )
+        .unwrap(),
+    )
+});
+
+pub static HTTP_REQUEST_DURATION_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency"),
+            &["method", "route", "status"],
+        )
+        .unwrap(),
+    )
+});
+
+pub static DB_POOL_CONNECTIONS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register(
+        IntGauge::new(
+            "db_pool_connections",
+            "Connections currently open in the database pool",
+        )
+        .unwrap(),
+    )
+});
+
+pub static DB_POOL_IDLE_CONNECTIONS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register(
+        IntGauge::new(
+            "db_pool_idle_connections",
+            "Idle connections in the database pool",
+        )
+        .unwrap(),
+    )
+});
+
+pub static DB_POOL_MAX_CONNECTIONS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register(
+        IntGauge::new(
+            "db_pool_max_connections",
+            "Maximum connections allowed in the database pool",
+        )
+        .unwrap(),
+    )
+});
+
+pub static EXTERNAL_REQUEST_DURATION_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "external_request_duration_seconds",
+                "Latency of calls to external services",
+            ),
+            &["service"],
+        )
+        .unwrap(),
+    )
+});
+
+pub static EXTERNAL_REQUEST_FAILURES_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "external_request_failures_total",
+                "Failed calls to external services",
+            ),
+            &["service"],
+        )
+        .unwrap(),
+    )
+});
+
+pub static MATCHES_INGESTED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(IntCounter::new("matches_ingested_total", "Matches ingested").unwrap())
+});
+
+pub static MATCHES_SKIPPED_BLACKLISTED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(
+        IntCounter::new(
+            "matches_skipped_blacklisted_total",
+            "Matches whose ratings were preserved because the map is blacklisted",
+        )
+        .unwrap(),
+    )
+});
+
+pub static PLAYERS_CREATED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    register(IntCounter::new("players_created_total", "Players created").unwrap())
+});
+
+pub async fn track_external<T, E, F>(service: &str, call: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let started_at = Instant::now();
+    let result = call.await;
+
+    EXTERNAL_REQUEST_DURATION_SECONDS
+        .with_label_values(&[service])
+        .observe(started_at.elapsed().as_secs_f64());
+    if result.is_err() {
+        EXTERNAL_REQUEST_FAILURES_TOTAL
+            .with_label_values(&[service])
+            .inc();
+    }
+
+    result
+}
+
+pub fn gather() -> String {
+    for counter in [
+        &MATCHES_INGESTED_TOTAL,
+        &MATCHES_SKIPPED_BLACKLISTED_TOTAL,
+        &PLAYERS_CREATED_TOTAL,
+    ] {
+        LazyLock::force(counter);
+    }
+    for gauge in [
+        &DB_POOL_CONNECTIONS,
+        &DB_POOL_IDLE_CONNECTIONS,
+        &DB_POOL_MAX_CONNECTIONS,
+    ] {
+        LazyLock::force(gauge);
+    }
+
+    TextEncoder::new()
+        .encode_to_string(&REGISTRY.gather())
+        .unwrap_or_default()
+}
@@ -1,3 +1,11 @@
+pub mod cache;
 pub mod env;
 pub mod error;
+pub mod events;
+pub mod metrics;
+pub mod rate_limit;
+pub mod request_id;
+pub mod secret;
+pub mod shutdown;
 pub mod state;
+pub mod validation;
@@ -0,0 +1,79 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::settings::RateLimitPolicy;
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    swept_at: Instant,
+}
+
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<Buckets>,
+}
+
+impl TokenBucket {
+    pub fn new(policy: RateLimitPolicy) -> Self {
+        Self {
+            capacity: f64::from(policy.burst),
+            refill_per_sec: f64::from(policy.requests_per_minute) / 60.0,
+            buckets: Mutex::new(Buckets {
+                by_key: HashMap::new(),
+                swept_at: Instant::now(),
+            }),
+        }
+    }
+
+    // Takes a token for `key`, or returns how long the caller has to wait for one.
+    pub fn acquire(&self, key: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if now.duration_since(buckets.swept_at) >= SWEEP_INTERVAL {
+            self.sweep(&mut buckets, now);
+        }
+
+        let bucket = buckets
+            .by_key
+            .entry(key.to_string())
+            .or_insert_with(|| Bucket {
+                tokens: self.capacity,
+                updated_at: now,
+            });
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64(
+            (1.0 - bucket.tokens) / self.refill_per_sec,
+        ))
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity)
+    }
+
+    // Full buckets carry no state worth keeping, so idle clients are forgotten.
+    fn sweep(&self, buckets: &mut Buckets, now: Instant) {
+        buckets
+            .by_key
+            .retain(|_, bucket| self.refilled(bucket, now) < self.capacity);
+        buckets.swept_at = now;
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use actix_web::rt::{self, signal, time::timeout};
+use tokio::sync::Notify;
+
+#[derive(Default)]
+struct Inner {
+    triggered: AtomicBool,
+    triggered_notify: Notify,
+    in_flight: AtomicUsize,
+    drained_notify: Notify,
+}
+
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+pub struct InFlightGuard {
+    inner: Arc<Inner>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.inner.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.inner.drained_notify.notify_waiters();
+        }
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trigger(&self) {
+        if !self.inner.triggered.swap(true, Ordering::SeqCst) {
+            self.inner.triggered_notify.notify_waiters();
+        }
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.inner.triggered.load(Ordering::SeqCst)
+    }
+
+    pub async fn triggered(&self) {
+        loop {
+            let notified = self.inner.triggered_notify.notified();
+            if self.is_triggered() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    // Returns `None` once shutdown has started so callers can refuse new work.
+    pub fn track(&self) -> Option<InFlightGuard> {
+        self.inner.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard {
+            inner: self.inner.clone(),
+        };
+        match self.is_triggered() {
+            true => None,
+            false => Some(guard),
+        }
+    }
+
+    pub async fn drained(&self, grace_period: Duration) -> bool {
+        let wait = async {
+            loop {
+                let notified = self.inner.drained_notify.notified();
+                if self.inner.in_flight.load(Ordering::SeqCst) == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        timeout(grace_period, wait).await.is_ok()
+    }
+
+    pub fn listen_for_signals(&self) {
+        let shutdown = self.clone();
+        rt::spawn(async move {
+            wait_for_signal().await;
+            info!("Shutdown signal received, stopping gracefully...");
+            shutdown.trigger();
+        });
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use signal::unix::{SignalKind, signal as unix_signal};
+
+    let mut terminate = unix_signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+    tokio::select! {
+        _ = signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = signal::ctrl_c().await;
+}
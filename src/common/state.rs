@@ -1,15 +1,28 @@
 use actix_web::web::Data;
 use sqlx::{MySql, Pool};
 
+use crate::common::{cache::Cache, events::EventBus, shutdown::Shutdown};
+
 #[derive(Clone)]
 pub struct State {
     pub db: Pool<MySql>,
+    pub events: EventBus,
+    pub shutdown: Shutdown,
+    pub cache: Cache,
 }
 
 pub trait DatabaseState {
     fn db(&self) -> &Pool<MySql>;
 }
 
+pub trait EventState {
+    fn events(&self) -> &EventBus;
+}
+
+pub trait CacheState {
+    fn cache(&self) -> &Cache;
+}
+
 impl DatabaseState for State {
     fn db(&self) -> &Pool<MySql> {
         &self.db
@@ -21,3 +34,27 @@ impl DatabaseState for Data<State> {
         &self.db
     }
 }
+
+impl EventState for State {
+    fn events(&self) -> &EventBus {
+        &self.events
+    }
+}
+
+impl EventState for Data<State> {
+    fn events(&self) -> &EventBus {
+        &self.events
+    }
+}
+
+impl CacheState for State {
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
+
+impl CacheState for Data<State> {
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
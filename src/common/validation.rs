@@ -0,0 +1,90 @@
+use actix_web::{Error, HttpRequest};
+use std::fmt::Display;
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+use crate::common::error::{AppError, FieldError, ServiceResult};
+
+fn collect_field_errors(prefix: &str, errors: &ValidationErrors, details: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let field = match (prefix.is_empty(), field.as_ref()) {
+            (true, "__all__") => "body".to_string(),
+            (false, "__all__") => prefix.to_string(),
+            (true, field) => field.to_string(),
+            (false, field) => format!("{prefix}.{field}"),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                details.extend(errors.iter().map(|error| {
+                    FieldError {
+                        field: field.clone(),
+                        reason: error
+                            .message
+                            .as_ref()
+                            .map_or_else(|| error.code.to_string(), |message| message.to_string()),
+                    }
+                }));
+            }
+            ValidationErrorsKind::Struct(errors) => {
+                collect_field_errors(&field, errors, details);
+            }
+            ValidationErrorsKind::List(errors) => {
+                for (index, errors) in errors {
+                    collect_field_errors(&format!("{field}[{index}]"), errors, details);
+                }
+            }
+        }
+    }
+}
+
+pub fn validate<T: Validate>(value: &T) -> ServiceResult<()> {
+    match value.validate() {
+        Ok(()) => Ok(()),
+        Err(errors) => {
+            let mut details = Vec::new();
+            collect_field_errors("", &errors, &mut details);
+            Err(AppError::Validation(details))
+        }
+    }
+}
+
+pub fn validate_all<T: Validate>(values: &[T]) -> ServiceResult<()> {
+    if values.is_empty() {
+        return Err(AppError::Validation(vec![FieldError {
+            field: "body".to_string(),
+            reason: "must contain at least one entry".to_string(),
+        }]));
+    }
+
+    let mut details = Vec::new();
+    for (index, value) in values.iter().enumerate() {
+        if let Err(errors) = value.validate() {
+            collect_field_errors(&format!("[{index}]"), &errors, &mut details);
+        }
+    }
+
+    match details.is_empty() {
+        true => Ok(()),
+        false => Err(AppError::Validation(details)),
+    }
+}
+
+pub fn validate_model(model: &str) -> Result<(), validator::ValidationError> {
+    match model.to_lowercase().as_str() {
+        "blue" | "red" => Ok(()),
+        _ => Err(validator::ValidationError::new("invalid_model")
+            .with_message("must be either 'blue' or 'red'".into())),
+    }
+}
+
+pub fn extractor_error_handler<E: Display>(
+    field: &'static str,
+) -> impl Fn(E, &HttpRequest) -> Error + Clone {
+    move |error, _req| {
+        AppError::Validation(vec![FieldError {
+            field: field.to_string(),
+            reason: error.to_string(),
+        }])
+        .into()
+    }
+}
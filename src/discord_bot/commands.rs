@@ -0,0 +1,236 @@
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        error::{AppError, ServiceResult},
+        state::{CacheState, DatabaseState},
+    },
+    models::{matches::MatchExtended, players::Player},
+    usecases::{matches, players},
+};
+
+const TOP_DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Deserialize)]
+pub struct InteractionData {
+    pub name: String,
+    #[serde(default)]
+    pub options: Vec<InteractionOption>,
+}
+
+#[derive(Deserialize)]
+pub struct InteractionOption {
+    pub name: String,
+    pub value: Value,
+}
+
+impl InteractionData {
+    fn option(&self, name: &str) -> Option<&Value> {
+        self.options
+            .iter()
+            .find(|option| option.name == name)
+            .map(|option| &option.value)
+    }
+
+    fn string_option(&self, name: &str) -> Option<&str> {
+        self.option(name).and_then(Value::as_str)
+    }
+
+    fn integer_option(&self, name: &str) -> Option<u64> {
+        self.option(name).and_then(Value::as_u64)
+    }
+}
+
+pub fn definitions() -> Value {
+    const STRING: u8 = 3;
+    const INTEGER: u8 = 4;
+
+    json!([
+        {
+            "name": "rank",
+            "description": "Show a player's rating and record",
+            "options": [
+                { "type": STRING, "name": "name", "description": "Steam ID, profile URL, Steam name or nickname", "required": true }
+            ]
+        },
+        {
+            "name": "top",
+            "description": "Show the top of the leaderboard",
+            "options": [
+                { "type": INTEGER, "name": "limit", "description": "Number of players", "min_value": 1, "max_value": 25 }
+            ]
+        },
+        {
+            "name": "match",
+            "description": "Show the result of a match",
+            "options": [
+                { "type": INTEGER, "name": "id", "description": "Match ID", "required": true }
+            ]
+        },
+        {
+            "name": "versus",
+            "description": "Show the head-to-head record of two players",
+            "options": [
+                { "type": STRING, "name": "player", "description": "Steam ID, profile URL, Steam name or nickname", "required": true },
+                { "type": STRING, "name": "opponent", "description": "Steam ID, profile URL, Steam name or nickname", "required": true }
+            ]
+        }
+    ])
+}
+
+pub async fn handle<T: DatabaseState + CacheState>(state: &T, data: &InteractionData) -> String {
+    let result = match data.name.as_str() {
+        "rank" => rank(state, data).await,
+        "top" => top(state, data).await,
+        "match" => show_match(state, data).await,
+        "versus" => versus(state, data).await,
+        _ => Ok("Unknown command.".to_string()),
+    };
+
+    result.unwrap_or_else(|e| e.message().to_string())
+}
+
+const MAX_CANDIDATES: usize = 10;
+
+enum PlayerLookup {
+    Found(Player),
+    Ambiguous(String),
+}
+
+// Several players can share a name, in that case the candidates are listed
+// instead of picking one of them.
+async fn find_player<T: DatabaseState + CacheState>(
+    state: &T,
+    value: &str,
+) -> ServiceResult<PlayerLookup> {
+    let mut found = players::find_players(state, value).await?;
+    match found.len() {
+        0 => Err(AppError::PlayerNotFound),
+        1 => Ok(PlayerLookup::Found(
+            players::fetch_player(state, found.remove(0).id).await?,
+        )),
+        count => {
+            let mut lines = vec![format!(
+                "{count} players match **{value}**, use a Steam ID instead:"
+            )];
+            lines.extend(
+                found
+                    .iter()
+                    .take(MAX_CANDIDATES)
+                    .map(|player| format!("• {} ({})", player.steam_name, player.steam_id)),
+            );
+            Ok(PlayerLookup::Ambiguous(lines.join("\n")))
+        }
+    }
+}
+
+async fn rank<T: DatabaseState + CacheState>(
+    state: &T,
+    data: &InteractionData,
+) -> ServiceResult<String> {
+    let name = data.string_option("name").unwrap_or_default();
+    let player = match find_player(state, name).await? {
+        PlayerLookup::Found(player) => player,
+        PlayerLookup::Ambiguous(candidates) => return Ok(candidates),
+    };
+
+    Ok(format!(
+        "**{}** — rating {:.2} ({}W / {}L, {} frags, {} deaths)",
+        player.steam_name,
+        player.stats.rating,
+        player.stats.wins,
+        player.stats.losses,
+        player.stats.total_frags,
+        player.stats.total_deaths,
+    ))
+}
+
+async fn top<T: DatabaseState + CacheState>(
+    state: &T,
+    data: &InteractionData,
+) -> ServiceResult<String> {
+    let limit = data
+        .integer_option("limit")
+        .map_or(TOP_DEFAULT_LIMIT, |limit| limit as u32);
+    let leaderboard = players::fetch_leaderboard(state, None, None, 1, limit).await?;
+
+    let lines: Vec<String> = leaderboard
+        .iter()
+        .enumerate()
+        .map(|(position, player)| {
+            format!(
+                "{}. **{}** — {:.2}",
+                position + 1,
+                player.steam_name,
+                player.stats.rating
+            )
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+async fn show_match<T: DatabaseState + CacheState>(
+    state: &T,
+    data: &InteractionData,
+) -> ServiceResult<String> {
+    let id = data.integer_option("id").unwrap_or_default();
+    let a_match = matches::fetch_match(state, id).await?;
+    Ok(format_match(&a_match))
+}
+
+async fn versus<T: DatabaseState + CacheState>(
+    state: &T,
+    data: &InteractionData,
+) -> ServiceResult<String> {
+    let player = match find_player(state, data.string_option("player").unwrap_or_default()).await? {
+        PlayerLookup::Found(player) => player,
+        PlayerLookup::Ambiguous(candidates) => return Ok(candidates),
+    };
+    let opponent =
+        match find_player(state, data.string_option("opponent").unwrap_or_default()).await? {
+            PlayerLookup::Found(opponent) => opponent,
+            PlayerLookup::Ambiguous(candidates) => return Ok(candidates),
+        };
+    let versus = players::fetch_versus(state, player.id, opponent.id).await?;
+
+    Ok(format!(
+        "**{}** vs **{}** — {} matches, {}W / {}L",
+        versus.player.steam_name,
+        versus.opponent.steam_name,
+        versus.matches,
+        versus.wins,
+        versus.losses,
+    ))
+}
+
+pub fn format_match(a_match: &MatchExtended) -> String {
+    let team_frags = |model: &str| -> i32 {
+        a_match
+            .match_details
+            .iter()
+            .filter(|detail| detail.model == model)
+            .map(|detail| detail.frags as i32)
+            .sum()
+    };
+
+    let mut lines = vec![format!(
+        "**{}** ({}) — Blue {} : {} Red",
+        a_match.map_name,
+        a_match.match_type,
+        team_frags("blue"),
+        team_frags("red"),
+    )];
+    lines.extend(a_match.match_details.iter().map(|detail| {
+        format!(
+            "• {} ({}) {}/{} — {:+.2} → {:.2}",
+            detail.nickname.as_deref().unwrap_or(&detail.steam_name),
+            detail.model,
+            detail.frags,
+            detail.deaths,
+            detail.rating_delta,
+            detail.rating_after_match,
+        )
+    }));
+    lines.join("\n")
+}
@@ -0,0 +1,186 @@
+mod commands;
+
+use std::{net::Ipv4Addr, time::Duration};
+
+use actix_web::{
+    App, HttpRequest, HttpResponse, HttpServer, post,
+    rt::{self, time::sleep},
+    web::{Bytes, Data},
+};
+use anyhow::{Context, bail};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    adapters::discord_api, common::state::State, lifecycle, settings::AppSettings,
+    usecases::matches,
+};
+
+const ANNOUNCE_POLL_INTERVAL: Duration = Duration::from_secs(15);
+const ANNOUNCE_BATCH_SIZE: u32 = 50;
+
+const INTERACTION_PING: u8 = 1;
+const INTERACTION_APPLICATION_COMMAND: u8 = 2;
+const RESPONSE_PONG: u8 = 1;
+const RESPONSE_CHANNEL_MESSAGE: u8 = 4;
+
+#[derive(Deserialize)]
+struct Interaction {
+    #[serde(rename = "type")]
+    kind: u8,
+    data: Option<commands::InteractionData>,
+}
+
+fn verify_signature(key: &VerifyingKey, req: &HttpRequest, body: &[u8]) -> bool {
+    let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok());
+    let (Some(signature), Some(timestamp)) = (
+        header("X-Signature-Ed25519"),
+        header("X-Signature-Timestamp"),
+    ) else {
+        return false;
+    };
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(signature) = Signature::from_slice(&signature) else {
+        return false;
+    };
+
+    let mut message = timestamp.as_bytes().to_vec();
+    message.extend_from_slice(body);
+    key.verify(&message, &signature).is_ok()
+}
+
+#[post("/interactions")]
+async fn interactions(
+    state: Data<State>,
+    key: Data<VerifyingKey>,
+    req: HttpRequest,
+    body: Bytes,
+) -> HttpResponse {
+    if !verify_signature(&key, &req, &body) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    let Ok(interaction) = serde_json::from_slice::<Interaction>(&body) else {
+        return HttpResponse::BadRequest().finish();
+    };
+
+    match (interaction.kind, interaction.data) {
+        (INTERACTION_PING, _) => HttpResponse::Ok().json(json!({ "type": RESPONSE_PONG })),
+        (INTERACTION_APPLICATION_COMMAND, Some(data)) => {
+            let content = commands::handle(&state, &data).await;
+            HttpResponse::Ok().json(json!({
+                "type": RESPONSE_CHANNEL_MESSAGE,
+                "data": { "content": content },
+            }))
+        }
+        _ => HttpResponse::BadRequest().finish(),
+    }
+}
+
+// Matches are processed by the API component, so the bot polls for new ones
+// instead of listening on the in-process event bus. Only processed matches are
+// announced, so rating changes are final.
+async fn announce_matches(state: State, channel_id: String, bot_token: String) {
+    let mut last_announced_id = None;
+
+    loop {
+        last_announced_id = match last_announced_id {
+            Some(last_id) => {
+                Some(announce_new_matches(&state, &channel_id, &bot_token, last_id).await)
+            }
+            // Matches processed before the bot started are not announced.
+            None => match matches::fetch_last_processed_id(&state).await {
+                Ok(max_id) => Some(max_id.unwrap_or(0)),
+                Err(e) => {
+                    warn!("Failed to fetch the last processed match: {e}");
+                    None
+                }
+            },
+        };
+
+        sleep(ANNOUNCE_POLL_INTERVAL).await;
+    }
+}
+
+// Announces every match processed after `last_announced_id`, a page at a time,
+// and returns the ID of the last one announced.
+pub async fn announce_new_matches(
+    state: &State,
+    channel_id: &str,
+    bot_token: &str,
+    mut last_announced_id: u64,
+) -> u64 {
+    loop {
+        let processed_matches =
+            match matches::fetch_processed_matches(state, last_announced_id, ANNOUNCE_BATCH_SIZE)
+                .await
+            {
+                Ok(processed_matches) => processed_matches,
+                Err(e) => {
+                    warn!("Failed to fetch processed matches: {e}");
+                    return last_announced_id;
+                }
+            };
+
+        for a_match in &processed_matches {
+            let content = commands::format_match(a_match);
+            if let Err(e) = discord_api::create_message(channel_id, bot_token, &content).await {
+                warn!("Failed to announce match ID {}: {e:#}", a_match.id);
+            }
+            last_announced_id = a_match.id;
+        }
+
+        if processed_matches.len() < ANNOUNCE_BATCH_SIZE as usize {
+            return last_announced_id;
+        }
+    }
+}
+
+pub async fn run(settings: &AppSettings) -> anyhow::Result<()> {
+    let application_id = settings
+        .discord_application_id
+        .clone()
+        .context("DISCORD_APPLICATION_ID is required for the Discord bot")?;
+    let bot_token = settings
+        .discord_bot_token
+        .as_ref()
+        .map(|token| token.expose().clone())
+        .context("DISCORD_BOT_TOKEN is required for the Discord bot")?;
+    let public_key = settings
+        .discord_public_key
+        .as_deref()
+        .context("DISCORD_PUBLIC_KEY is required for the Discord bot")?;
+    // Commands resolve Steam IDs and profile URLs through the Steam API.
+    if settings.steam_api_key.is_none() {
+        bail!("STEAM_API_KEY is required for the Discord bot");
+    }
+
+    let public_key: [u8; 32] = hex::decode(public_key)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("DISCORD_PUBLIC_KEY must be 32 bytes"))?;
+    let verifying_key = VerifyingKey::from_bytes(&public_key)?;
+
+    let state = lifecycle::initialize_state(settings).await?;
+
+    info!("Registering Discord slash commands...");
+    discord_api::register_commands(&application_id, &bot_token, &commands::definitions()).await?;
+
+    if let Some(channel_id) = settings.discord_channel_id.clone() {
+        info!("Announcing processed matches to channel {channel_id}");
+        rt::spawn(announce_matches(state.clone(), channel_id, bot_token));
+    }
+
+    HttpServer::new(move || {
+        App::new()
+            .app_data(Data::new(state.clone()))
+            .app_data(Data::new(verifying_key))
+            .service(interactions)
+    })
+    .bind((Ipv4Addr::UNSPECIFIED, settings.app_port))?
+    .run()
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+use actix_web::rt::{self, time::sleep};
+
+use crate::{common::state::State, usecases::enrichments};
+
+const ENRICHMENT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+pub fn spawn_worker(state: State) {
+    rt::spawn(async move {
+        while !state.shutdown.is_triggered() {
+            if let Err(e) = enrichments::enrich_due(&state).await {
+                error!("Failed to enrich pending Steam profiles: {e}");
+            }
+            sleep(ENRICHMENT_POLL_INTERVAL).await;
+        }
+    });
+}
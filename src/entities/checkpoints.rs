@@ -0,0 +1,7 @@
+use sqlx::prelude::FromRow;
+
+#[derive(FromRow)]
+pub struct Checkpoint {
+    pub last_id: u64,
+    pub until_id: Option<u64>,
+}
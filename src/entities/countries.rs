@@ -0,0 +1,9 @@
+use sqlx::prelude::FromRow;
+
+#[derive(FromRow)]
+pub struct CountrySummary {
+    pub country: String,
+    pub players: i64,
+    pub ranked_players: i64,
+    pub average_rating: Option<f64>,
+}
@@ -0,0 +1,8 @@
+use sqlx::prelude::FromRow;
+
+#[derive(FromRow)]
+pub struct PendingEnrichment {
+    pub player_id: u64,
+    pub steam_id: String,
+    pub attempts: u32,
+}
@@ -4,6 +4,7 @@ use sqlx::prelude::FromRow;
 pub struct MatchDetail {
     pub id: u64,
     pub player_id: u64,
+    pub nickname: Option<String>,
     pub steam_name: String,
     pub steam_id: String,
     pub steam_avatar_url: String,
@@ -1,4 +1,8 @@
+pub mod checkpoints;
+pub mod countries;
+pub mod enrichments;
 pub mod match_details;
 pub mod matches;
 pub mod players;
 pub mod stats;
+pub mod webhooks;
@@ -24,3 +24,9 @@ pub struct PlayerHistoryCapture {
     pub captured_at: DateTime<Utc>,
     pub rating: f64,
 }
+
+#[derive(FromRow)]
+pub struct VersusRecord {
+    pub matches: i64,
+    pub wins: i64,
+}
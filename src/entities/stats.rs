@@ -9,4 +9,5 @@ pub struct Stats {
     pub losses: u32,
     pub total_frags: i32,
     pub total_deaths: i32,
+    pub leaderboard_rank: Option<u32>,
 }
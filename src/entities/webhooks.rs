@@ -0,0 +1,20 @@
+use sqlx::prelude::FromRow;
+
+#[derive(FromRow)]
+pub struct Webhook {
+    pub id: u64,
+    pub url: String,
+    pub secret: String,
+    pub events: String,
+}
+
+#[derive(FromRow)]
+pub struct WebhookDelivery {
+    pub id: u64,
+    pub webhook_id: u64,
+    pub url: String,
+    pub secret: String,
+    pub event: String,
+    pub payload: String,
+    pub attempts: u32,
+}
@@ -1,64 +1,90 @@
 use crate::{
+    cli::FixMatchesArgs,
     entities::match_details::MatchDetail,
     lifecycle,
     repositories::{match_details, matches},
     settings::AppSettings,
 };
 
+const JOB_NAME: &str = "fixer";
+
 // XXX: This is temporary to fix existing matches that have uneven teams or very low frags.
 // This happened due to the old api not having proper validation for these cases
-pub async fn fix_matches(settings: &AppSettings) -> anyhow::Result<()> {
+pub async fn fix_matches(settings: &AppSettings, args: FixMatchesArgs) -> anyhow::Result<()> {
     info!("Starting match fixes...");
 
     let state = lifecycle::initialize_state(settings).await?;
+    let dry_run = args.batch.dry_run;
 
-    let matches = matches::fetch_all_matches(&state).await?;
+    let mut last_checked_id = lifecycle::resume_point(&state, JOB_NAME, args.from_match).await?;
+    loop {
+        let batch =
+            matches::fetch_batch(&state, last_checked_id.unwrap_or(0), args.batch.batch_size)
+                .await?;
+        if batch.is_empty() {
+            break;
+        }
 
-    for match_entry in matches {
-        let mut to_delete = false;
-        let match_details = match_details::fetch_match_details(&state, match_entry.id).await?;
+        for match_entry in batch {
+            if state.shutdown.is_triggered() {
+                if !dry_run {
+                    lifecycle::checkpoint(&state, JOB_NAME, last_checked_id, None).await?;
+                }
+                return Ok(());
+            }
+            last_checked_id = Some(match_entry.id);
 
-        if !match_details.is_empty() {
-            let blue_team: Vec<MatchDetail> = match_details
-                .iter()
-                .filter(|detail| detail.model == "blue")
-                .cloned()
-                .collect();
-            let red_team: Vec<MatchDetail> = match_details
-                .iter()
-                .filter(|detail| detail.model == "red")
-                .cloned()
-                .collect();
+            let mut to_delete = false;
+            let match_details = match_details::fetch_match_details(&state, match_entry.id).await?;
 
-            if blue_team.len() != red_team.len() {
-                warn!(
-                    "Match ID: {} has uneven teams. Blue team size: {}, Red team size: {}",
-                    match_entry.id,
-                    blue_team.len(),
-                    red_team.len()
-                );
-                to_delete = true;
-            }
+            if !match_details.is_empty() {
+                let blue_team: Vec<MatchDetail> = match_details
+                    .iter()
+                    .filter(|detail| detail.model == "blue")
+                    .cloned()
+                    .collect();
+                let red_team: Vec<MatchDetail> = match_details
+                    .iter()
+                    .filter(|detail| detail.model == "red")
+                    .cloned()
+                    .collect();
 
-            let blue_team_frags: i16 = blue_team.iter().map(|detail| detail.frags).sum();
-            let red_team_frags: i16 = red_team.iter().map(|detail| detail.frags).sum();
+                if blue_team.len() != red_team.len() {
+                    warn!(
+                        "Match ID: {} has uneven teams. Blue team size: {}, Red team size: {}",
+                        match_entry.id,
+                        blue_team.len(),
+                        red_team.len()
+                    );
+                    to_delete = true;
+                }
 
-            if blue_team_frags < 10 && red_team_frags < 10 {
-                warn!(
-                    "Match ID: {} has low frags. Blue team frags: {}, Red team frags: {}",
-                    match_entry.id, blue_team_frags, red_team_frags
-                );
-                to_delete = true;
-            }
+                let blue_team_frags: i16 = blue_team.iter().map(|detail| detail.frags).sum();
+                let red_team_frags: i16 = red_team.iter().map(|detail| detail.frags).sum();
+
+                if blue_team_frags < 10 && red_team_frags < 10 {
+                    warn!(
+                        "Match ID: {} has low frags. Blue team frags: {}, Red team frags: {}",
+                        match_entry.id, blue_team_frags, red_team_frags
+                    );
+                    to_delete = true;
+                }
 
-            if to_delete {
-                info!("Deleting match ID: {} and its details", match_entry.id);
-                match_details::delete_by_match_id(&state, match_entry.id).await?;
-                matches::delete_match(&state, match_entry.id).await?;
+                if to_delete && dry_run {
+                    info!("Would delete match ID: {} and its details", match_entry.id);
+                } else if to_delete {
+                    info!("Deleting match ID: {} and its details", match_entry.id);
+                    match_details::delete_by_match_id(&state, match_entry.id).await?;
+                    matches::delete_match(&state, match_entry.id).await?;
+                }
             }
         }
     }
 
+    if !dry_run {
+        lifecycle::complete(&state, JOB_NAME).await?;
+    }
+
     info!("Match fixes completed.");
 
     Ok(())
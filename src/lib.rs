@@ -4,12 +4,18 @@ extern crate tracing;
 pub mod adapters;
 pub mod api;
 pub mod backfill;
+pub mod cli;
 pub mod common;
+pub mod discord_bot;
+pub mod enrichment;
 pub mod entities;
 pub mod fixer;
 pub mod lifecycle;
+pub mod migrate;
 pub mod models;
 pub mod processor;
+pub mod profile_refresher;
 pub mod repositories;
 pub mod settings;
 pub mod usecases;
+pub mod webhooks;
@@ -1,6 +1,14 @@
-use sqlx::{MySql, Pool, mysql::MySqlPoolOptions};
+use anyhow::bail;
+use sqlx::{MySql, Pool, migrate::Migrator, mysql::MySqlPoolOptions};
 
-use crate::{common::state::State, settings::AppSettings};
+use crate::{
+    common::{cache::Cache, events::EventBus, shutdown::Shutdown, state::State},
+    repositories::checkpoints,
+    settings::AppSettings,
+    usecases::health::check_migrations,
+};
+
+pub static MIGRATOR: Migrator = sqlx::migrate!();
 
 pub fn initialize_logging(settings: &AppSettings) {
     tracing_subscriber::fmt()
@@ -17,12 +25,84 @@ pub async fn initialize_state(settings: &AppSettings) -> anyhow::Result<State> {
     let db = initialize_db(settings)
         .await
         .expect("Failed to connect to the database");
-    Ok(State { db })
+    let shutdown = Shutdown::new();
+    shutdown.listen_for_signals();
+
+    let cache = Cache::new(&settings.cache).await?;
+
+    Ok(State {
+        db,
+        events: EventBus::new(),
+        shutdown,
+        cache,
+    })
 }
 
 pub fn initialize_db(settings: &AppSettings) -> impl Future<Output = sqlx::Result<Pool<MySql>>> {
     MySqlPoolOptions::new()
         .acquire_timeout(settings.database_wait_timeout)
         .max_connections(settings.database_max_connections as _)
-        .connect(&settings.database_url)
+        .connect(settings.database_url.expose())
+}
+
+pub async fn run_migrations(state: &State) -> anyhow::Result<()> {
+    MIGRATOR.run(&state.db).await?;
+    Ok(())
+}
+
+pub async fn ensure_schema_up_to_date(state: &State) -> anyhow::Result<()> {
+    let migrations = check_migrations(state).await?;
+    if !migrations.up_to_date {
+        let pending: Vec<String> = migrations
+            .pending
+            .iter()
+            .map(|migration| format!("{} ({})", migration.version, migration.description))
+            .collect();
+        bail!(
+            "Database schema is behind the binary, pending migrations: {}",
+            pending.join(", ")
+        );
+    }
+    Ok(())
+}
+
+// An explicit starting ID overrides the saved checkpoint; both are exclusive lower bounds.
+pub async fn resume_point(
+    state: &State,
+    job: &str,
+    from_id: Option<u64>,
+) -> anyhow::Result<Option<u64>> {
+    match from_id {
+        Some(from_id) => Ok(Some(from_id.saturating_sub(1))),
+        None => Ok(checkpoints::fetch(state, job)
+            .await?
+            .map(|checkpoint| checkpoint.last_id)),
+    }
+}
+
+// `until_id` bounds jobs that must not go past the IDs that existed when they started.
+pub async fn checkpoint(
+    state: &State,
+    job: &str,
+    last_id: Option<u64>,
+    until_id: Option<u64>,
+) -> anyhow::Result<()> {
+    state.cache.invalidate_all().await;
+    match last_id {
+        Some(last_id) => {
+            checkpoints::save(state, job, last_id, until_id).await?;
+            info!("Shutdown requested, {job} checkpointed after ID: {last_id}");
+        }
+        None => info!("Shutdown requested, {job} stopped before processing anything"),
+    }
+    Ok(())
+}
+
+// Jobs write to the database directly, so cached responses are dropped once they
+// stop. Only the redis backend is shared with the API, the memory backend relies
+// on cache.ttl_secs.
+pub async fn complete(state: &State, job: &str) -> anyhow::Result<()> {
+    state.cache.invalidate_all().await;
+    checkpoints::clear(state, job).await?;
+    Ok(())
 }
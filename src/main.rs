@@ -1,18 +1,32 @@
-use core::panic;
-use shion::settings::AppSettings;
-use shion::{api, backfill, fixer, lifecycle, processor};
+use clap::Parser;
+use shion::cli::{BackfillCommand, Cli, Command};
+use shion::settings;
+use shion::{api, backfill, discord_bot, fixer, lifecycle, migrate, processor, profile_refresher};
 
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
-    let settings = AppSettings::get();
+    let cli = Cli::parse();
+    let settings = settings::init()?;
     lifecycle::initialize_logging(settings);
+    tracing::debug!("Loaded settings: {settings:?}");
 
-    match settings.app_component.as_str() {
-        "api" => api::serve(settings).await,
-        "backfill_stats" => backfill::backfill_stats(settings).await,
-        "backfill_countries" => backfill::backfill_countries(settings).await,
-        "fix_matches" => fixer::fix_matches(settings).await,
-        "processor" => processor::reprocess_all(settings).await,
-        _ => panic!("Unknown app component"),
+    let command = match cli.command {
+        Some(command) => command,
+        None => Command::from_app_component(settings.app_component.as_deref())?,
+    };
+
+    match command {
+        Command::Serve => api::serve(settings).await,
+        Command::DiscordBot => discord_bot::run(settings).await,
+        Command::Migrate => migrate::run(settings).await,
+        Command::Reprocess(args) => processor::reprocess_all(settings, args).await,
+        Command::FixMatches(args) => fixer::fix_matches(settings, args).await,
+        Command::RefreshProfiles(args) => profile_refresher::run(settings, args).await,
+        Command::Backfill(BackfillCommand::Stats(args)) => {
+            backfill::backfill_stats(settings, args).await
+        }
+        Command::Backfill(BackfillCommand::Countries(args)) => {
+            backfill::backfill_countries(settings, args).await
+        }
     }
 }
@@ -0,0 +1,11 @@
+use crate::{lifecycle, settings::AppSettings};
+
+pub async fn run(settings: &AppSettings) -> anyhow::Result<()> {
+    info!("Applying database migrations...");
+
+    let state = lifecycle::initialize_state(settings).await?;
+    lifecycle::run_migrations(&state).await?;
+
+    info!("Database migrations completed.");
+    Ok(())
+}
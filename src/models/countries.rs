@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::entities::countries::CountrySummary as CountrySummaryEntity;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Region {
+    Eu,
+    Na,
+    Sa,
+    Cis,
+    Asia,
+    Me,
+    Africa,
+    Oceania,
+}
+
+const EU: &[&str] = &[
+    "ad", "al", "at", "ba", "be", "bg", "ch", "cy", "cz", "de", "dk", "ee", "es", "fi", "fo", "fr",
+    "gb", "gi", "gr", "hr", "hu", "ie", "im", "is", "it", "li", "lt", "lu", "lv", "mc", "me", "mk",
+    "mt", "nl", "no", "pl", "pt", "ro", "rs", "se", "si", "sk", "sm", "tr", "va", "xk",
+];
+const NA: &[&str] = &[
+    "ag", "bb", "bs", "bz", "ca", "cr", "cu", "dm", "do", "gd", "gt", "hn", "ht", "jm", "kn", "lc",
+    "mx", "ni", "pa", "pr", "sv", "tt", "us", "vc",
+];
+const SA: &[&str] = &[
+    "ar", "bo", "br", "cl", "co", "ec", "gf", "gy", "pe", "py", "sr", "uy", "ve",
+];
+const CIS: &[&str] = &[
+    "am", "az", "by", "ge", "kg", "kz", "md", "ru", "tj", "tm", "ua", "uz",
+];
+const ASIA: &[&str] = &[
+    "af", "bd", "bn", "bt", "cn", "hk", "id", "in", "jp", "kh", "kp", "kr", "la", "lk", "mm", "mn",
+    "mo", "mv", "my", "np", "ph", "pk", "sg", "th", "tl", "tw", "vn",
+];
+const ME: &[&str] = &[
+    "ae", "bh", "il", "iq", "ir", "jo", "kw", "lb", "om", "ps", "qa", "sa", "sy", "ye",
+];
+const AFRICA: &[&str] = &[
+    "ao", "bf", "bi", "bj", "bw", "cd", "cf", "cg", "ci", "cm", "cv", "dj", "dz", "eg", "er", "et",
+    "ga", "gh", "gm", "gn", "gq", "gw", "ke", "km", "lr", "ls", "ly", "ma", "mg", "ml", "mr", "mu",
+    "mw", "mz", "na", "ne", "ng", "rw", "sc", "sd", "sl", "sn", "so", "ss", "st", "sz", "td", "tg",
+    "tn", "tz", "ug", "za", "zm", "zw",
+];
+const OCEANIA: &[&str] = &[
+    "au", "fj", "fm", "ki", "mh", "nc", "nr", "nz", "pf", "pg", "pw", "sb", "to", "tv", "vu", "ws",
+];
+
+impl Region {
+    pub const ALL: &[Region] = &[
+        Region::Eu,
+        Region::Na,
+        Region::Sa,
+        Region::Cis,
+        Region::Asia,
+        Region::Me,
+        Region::Africa,
+        Region::Oceania,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Region::Eu => "eu",
+            Region::Na => "na",
+            Region::Sa => "sa",
+            Region::Cis => "cis",
+            Region::Asia => "asia",
+            Region::Me => "me",
+            Region::Africa => "africa",
+            Region::Oceania => "oceania",
+        }
+    }
+
+    pub const fn countries(&self) -> &'static [&'static str] {
+        match self {
+            Region::Eu => EU,
+            Region::Na => NA,
+            Region::Sa => SA,
+            Region::Cis => CIS,
+            Region::Asia => ASIA,
+            Region::Me => ME,
+            Region::Africa => AFRICA,
+            Region::Oceania => OCEANIA,
+        }
+    }
+
+    // Countries are stored as lowercase ISO 3166-1 alpha-2 codes, with "xx" for unknown.
+    pub fn of(country: &str) -> Option<Region> {
+        Region::ALL
+            .iter()
+            .copied()
+            .find(|region| region.countries().contains(&country))
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CountrySummary {
+    pub country: String,
+    pub region: Option<Region>,
+    pub players: u32,
+    pub ranked_players: u32,
+    pub average_rating: Option<f64>,
+}
+
+impl From<CountrySummaryEntity> for CountrySummary {
+    fn from(value: CountrySummaryEntity) -> Self {
+        Self {
+            region: Region::of(&value.country),
+            country: value.country,
+            players: value.players as u32,
+            ranked_players: value.ranked_players as u32,
+            average_rating: value.average_rating,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Clone, ToSchema)]
+pub struct MatchProcessed {
+    pub id: u64,
+    pub server_ip: String,
+    pub match_date: DateTime<Utc>,
+    pub map_name: String,
+    pub match_type: String,
+    pub players: Vec<PlayerRatingChange>,
+}
+
+#[derive(Serialize, Clone, ToSchema)]
+pub struct PlayerRatingChange {
+    pub player_id: u64,
+    pub steam_id: String,
+    pub steam_name: String,
+    pub nickname: Option<String>,
+    pub model: String,
+    pub frags: i16,
+    pub deaths: i16,
+    pub rating_after_match: f64,
+    pub rating_delta: f64,
+}
+
+#[derive(Serialize, Clone, ToSchema)]
+pub struct PlayerCreated {
+    pub id: u64,
+    pub steam_id: String,
+    pub steam_name: String,
+    pub steam_avatar_url: String,
+    pub country: String,
+}
+
+#[derive(Serialize, Clone, ToSchema)]
+pub struct LeaderboardLeaderChanged {
+    pub player_id: u64,
+    pub steam_id: String,
+    pub steam_name: String,
+    pub rating: f64,
+    pub previous_player_id: Option<u64>,
+}
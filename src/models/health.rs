@@ -0,0 +1,30 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct Readiness {
+    pub ready: bool,
+    pub shutting_down: bool,
+    pub database: DatabaseHealth,
+    pub migrations: MigrationsHealth,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DatabaseHealth {
+    pub reachable: bool,
+    pub latency_ms: Option<u128>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MigrationsHealth {
+    pub up_to_date: bool,
+    pub applied: Vec<i64>,
+    pub pending: Vec<PendingMigration>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PendingMigration {
+    pub version: i64,
+    pub description: String,
+}
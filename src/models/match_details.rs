@@ -1,11 +1,13 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::entities::match_details::MatchDetail as MatchDetailEntity;
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct MatchDetail {
     pub id: u64,
     pub player_id: u64,
+    pub nickname: Option<String>,
     pub steam_name: String,
     pub steam_id: String,
     pub steam_avatar_url: String,
@@ -25,6 +27,7 @@ impl From<MatchDetailEntity> for MatchDetail {
         Self {
             id: value.id,
             player_id: value.player_id,
+            nickname: value.nickname,
             steam_name: value.steam_name,
             steam_id: value.steam_id,
             steam_avatar_url: value.steam_avatar_url,
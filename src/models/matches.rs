@@ -1,19 +1,20 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::{
     entities::match_details::MatchDetail as MatchDetailEntity,
     entities::matches::Match as MatchEntity, models::match_details::MatchDetail,
 };
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct Match {
     pub id: u64,
     pub server_ip: String,
     pub map_name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct MatchExtended {
     pub id: u64,
     pub server_ip: String,
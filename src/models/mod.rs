@@ -1,3 +1,6 @@
+pub mod countries;
+pub mod events;
+pub mod health;
 pub mod location;
 pub mod match_details;
 pub mod matches;
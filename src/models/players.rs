@@ -1,12 +1,13 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::{
     entities::players::{Player as PlayerEntity, PlayerHistory as PlayerHistoryEntity},
     models::stats::Stats,
 };
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct Player {
     pub id: u64,
     pub steam_id: String,
@@ -16,12 +17,27 @@ pub struct Player {
     pub stats: Stats,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LeaderboardPage {
+    pub total_ranked: u32,
+    pub players: Vec<Player>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Versus {
+    pub player: Player,
+    pub opponent: Player,
+    pub matches: u32,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct PlayerHistory {
     pub captures: Vec<PlayerHistoryCapture>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct PlayerHistoryCapture {
     pub captured_at: DateTime<Utc>,
     pub rating: f64,
@@ -1,8 +1,9 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::entities::stats::Stats as StatsEntity;
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct Stats {
     pub player_id: u64,
     pub rating: f64,
@@ -11,6 +12,7 @@ pub struct Stats {
     pub losses: u32,
     pub total_frags: i32,
     pub total_deaths: i32,
+    pub rank: Option<u32>,
 }
 
 impl From<StatsEntity> for Stats {
@@ -23,6 +25,7 @@ impl From<StatsEntity> for Stats {
             losses: value.losses,
             total_frags: value.total_frags,
             total_deaths: value.total_deaths,
+            rank: value.leaderboard_rank,
         }
     }
 }
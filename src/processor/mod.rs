@@ -1,25 +1,108 @@
-use anyhow::Ok;
+use anyhow::{Ok, bail};
 
 use crate::{
+    cli::ReprocessArgs,
+    entities::checkpoints::Checkpoint,
     lifecycle,
-    repositories::{matches, stats},
+    repositories::{checkpoints, matches, stats},
     settings::AppSettings,
     usecases::match_details,
 };
 
-pub async fn reprocess_all(settings: &AppSettings) -> anyhow::Result<()> {
+const JOB_NAME: &str = "processor";
+
+pub async fn reprocess_all(settings: &AppSettings, args: ReprocessArgs) -> anyhow::Result<()> {
     info!("Starting historical data processing...");
 
     let state = lifecycle::initialize_state(settings).await?;
+    let dry_run = args.batch.dry_run;
+
+    // Checkpoints saved before runs were bounded can't tell which matches the
+    // API applied live in the meantime, so they are not resumed.
+    let checkpoint = match checkpoints::fetch(&state, JOB_NAME).await? {
+        Some(checkpoint) if checkpoint.until_id.is_none() => {
+            warn!("Ignoring checkpoint without an upper bound, starting over");
+            None
+        }
+        checkpoint => checkpoint,
+    };
+
+    // Current stats already include every match up to the checkpoint, so starting
+    // anywhere else would apply matches twice or skip them.
+    if let Some(from_match) = args.from_match {
+        let last_id = checkpoint.as_ref().map(|checkpoint| checkpoint.last_id);
+        if last_id != Some(from_match.saturating_sub(1)) {
+            bail!(
+                "--from-match {from_match} does not follow the saved checkpoint ({}), \
+                 run without it to reset stats and replay every match",
+                last_id.map_or("none".to_string(), |last_id| last_id.to_string())
+            );
+        }
+    }
+
+    // Matches ingested after the run started are applied live by the API, so
+    // only the matches that existed at that point are replayed, even on resume.
+    let (resume_point, until_id) = match checkpoint {
+        Some(Checkpoint {
+            last_id,
+            until_id: Some(until_id),
+        }) => {
+            info!("Resuming after match ID: {last_id}, up to match ID: {until_id}");
+            (Some(last_id), until_id)
+        }
+        _ => {
+            if dry_run {
+                info!("Would reset all player stats");
+            } else {
+                stats::reset_all_player_stats(
+                    &state,
+                    settings.rating.initial_rating,
+                    settings.rating.initial_uncertainty,
+                )
+                .await?;
+            }
+            (None, matches::fetch_max_id(&state).await?.unwrap_or(0))
+        }
+    };
 
-    let matches = matches::fetch_all_matches(&state).await?;
+    let mut last_processed_id = resume_point;
+    loop {
+        let batch: Vec<_> = matches::fetch_batch(
+            &state,
+            last_processed_id.unwrap_or(0),
+            args.batch.batch_size,
+        )
+        .await?
+        .into_iter()
+        .take_while(|match_entry| match_entry.id <= until_id)
+        .collect();
+        if batch.is_empty() {
+            break;
+        }
 
-    stats::reset_all_player_stats(&state).await?;
+        for match_entry in batch {
+            if state.shutdown.is_triggered() {
+                if !dry_run {
+                    stats::refresh_ranks(&state).await?;
+                    lifecycle::checkpoint(&state, JOB_NAME, last_processed_id, Some(until_id))
+                        .await?;
+                }
+                return Ok(());
+            }
 
-    for match_entry in matches {
-        info!("Reprocessing match ID: {}", match_entry.id);
+            if dry_run {
+                info!("Would reprocess match ID: {}", match_entry.id);
+            } else {
+                info!("Reprocessing match ID: {}", match_entry.id);
+                let _ = match_details::process_match(&state, match_entry.id, true).await;
+            }
+            last_processed_id = Some(match_entry.id);
+        }
+    }
 
-        let _ = match_details::process_match(&state, match_entry.id).await;
+    if !dry_run {
+        stats::refresh_ranks(&state).await?;
+        lifecycle::complete(&state, JOB_NAME).await?;
     }
 
     info!("Historical data processing completed.");
@@ -0,0 +1,77 @@
+use actix_web::rt::time::sleep;
+use anyhow::bail;
+
+use crate::{
+    adapters::steam_api, cli::RefreshProfilesArgs, common::state::State, lifecycle,
+    repositories::players, settings::AppSettings, usecases::players as player_usecases,
+};
+
+pub async fn run(settings: &AppSettings, args: RefreshProfilesArgs) -> anyhow::Result<()> {
+    if settings.steam_api_key.is_none() {
+        bail!("STEAM_API_KEY is required to refresh Steam profiles");
+    }
+
+    info!("Starting Steam profile refresher...");
+
+    let state = lifecycle::initialize_state(settings).await?;
+
+    loop {
+        refresh_all(&state, args.dry_run).await?;
+        if args.once {
+            break;
+        }
+
+        info!(
+            "Next Steam profile refresh in {:?}",
+            settings.profile_refresh_interval
+        );
+        tokio::select! {
+            _ = state.shutdown.triggered() => break,
+            _ = sleep(settings.profile_refresh_interval) => {}
+        }
+    }
+
+    info!("Steam profile refresher stopped.");
+
+    Ok(())
+}
+
+async fn refresh_all(state: &State, dry_run: bool) -> anyhow::Result<()> {
+    let mut last_player_id = 0;
+    let mut updated = 0;
+    loop {
+        if state.shutdown.is_triggered() {
+            return Ok(());
+        }
+
+        let batch =
+            players::fetch_batch(state, last_player_id, steam_api::MAX_SUMMARIES_PER_REQUEST)
+                .await?;
+        let Some(last_player) = batch.last() else {
+            break;
+        };
+        last_player_id = last_player.id;
+
+        // A failed Steam request only skips this batch, the next sweep retries it.
+        match player_usecases::refresh_steam_profiles(state, &batch, dry_run).await {
+            Ok(count) => updated += count,
+            Err(e) => warn!(
+                "Failed refreshing Steam profiles up to player ID: {}: {}",
+                last_player_id, e
+            ),
+        }
+    }
+
+    // The refresher runs in its own process: with the redis backend this clears
+    // the cache the API reads from, with the memory backend it only clears this
+    // process and the API serves the old profiles until cache.ttl_secs expires.
+    if updated > 0 && !dry_run {
+        state.cache.invalidate_all().await;
+    }
+    info!(
+        "Steam profile refresh completed, {} profiles changed.",
+        updated
+    );
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use crate::common::state::DatabaseState;
+
+const TABLE_NAME: &str = "player_alias";
+
+pub async fn upsert<T: DatabaseState>(
+    state: &T,
+    player_id: u64,
+    nickname: &str,
+) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO `",
+        TABLE_NAME,
+        "` (player_id, nickname) VALUES (?, ?) ",
+        "ON DUPLICATE KEY UPDATE last_seen_at = NOW()"
+    );
+
+    sqlx::query(QUERY)
+        .bind(player_id)
+        .bind(nickname)
+        .execute(state.db())
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::{common::state::DatabaseState, entities::checkpoints::Checkpoint};
+
+const TABLE_NAME: &str = "job_checkpoint";
+
+pub async fn fetch<T: DatabaseState>(state: &T, job: &str) -> sqlx::Result<Option<Checkpoint>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT last_id, until_id FROM `",
+        TABLE_NAME,
+        "` WHERE job = ?"
+    );
+
+    sqlx::query_as::<_, Checkpoint>(QUERY)
+        .bind(job)
+        .fetch_optional(state.db())
+        .await
+}
+
+pub async fn save<T: DatabaseState>(
+    state: &T,
+    job: &str,
+    last_id: u64,
+    until_id: Option<u64>,
+) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO `",
+        TABLE_NAME,
+        "` (job, last_id, until_id) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE last_id = ?, until_id = ?"
+    );
+
+    sqlx::query(QUERY)
+        .bind(job)
+        .bind(last_id)
+        .bind(until_id)
+        .bind(last_id)
+        .bind(until_id)
+        .execute(state.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn clear<T: DatabaseState>(state: &T, job: &str) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!("DELETE FROM `", TABLE_NAME, "` WHERE job = ?");
+
+    sqlx::query(QUERY).bind(job).execute(state.db()).await?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::{common::state::DatabaseState, entities::enrichments::PendingEnrichment};
+
+const TABLE_NAME: &str = "player_enrichment";
+
+pub async fn create<T: DatabaseState>(state: &T, player_id: u64) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT IGNORE INTO `",
+        TABLE_NAME,
+        "` (player_id) VALUES (?)"
+    );
+
+    sqlx::query(QUERY)
+        .bind(player_id)
+        .execute(state.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_due<T: DatabaseState>(
+    state: &T,
+    max_attempts: u32,
+    limit: u32,
+) -> sqlx::Result<Vec<PendingEnrichment>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT e.player_id, p.steam_id, e.attempts FROM `",
+        TABLE_NAME,
+        "` e ",
+        "JOIN player p ON e.player_id = p.id ",
+        "WHERE e.attempts < ? AND e.next_attempt_at <= NOW() ",
+        "ORDER BY e.next_attempt_at ASC LIMIT ?"
+    );
+
+    sqlx::query_as::<_, PendingEnrichment>(QUERY)
+        .bind(max_attempts)
+        .bind(limit)
+        .fetch_all(state.db())
+        .await
+}
+
+pub async fn delete<T: DatabaseState>(state: &T, player_id: u64) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!("DELETE FROM `", TABLE_NAME, "` WHERE player_id = ?");
+
+    sqlx::query(QUERY)
+        .bind(player_id)
+        .execute(state.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn mark_failed<T: DatabaseState>(
+    state: &T,
+    player_id: u64,
+    retry_in_secs: u64,
+    error: &str,
+) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE `",
+        TABLE_NAME,
+        "` SET attempts = attempts + 1, next_attempt_at = DATE_ADD(NOW(), INTERVAL ? SECOND), last_error = LEFT(?, 512) ",
+        "WHERE player_id = ?"
+    );
+
+    sqlx::query(QUERY)
+        .bind(retry_in_secs)
+        .bind(error)
+        .bind(player_id)
+        .execute(state.db())
+        .await?;
+    Ok(())
+}
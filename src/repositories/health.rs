@@ -0,0 +1,15 @@
+use crate::common::state::DatabaseState;
+
+pub async fn ping<T: DatabaseState>(state: &T) -> sqlx::Result<()> {
+    sqlx::query("SELECT 1").execute(state.db()).await?;
+    Ok(())
+}
+
+pub async fn fetch_applied_migrations<T: DatabaseState>(state: &T) -> sqlx::Result<Vec<i64>> {
+    const QUERY: &str = "SELECT version FROM `_sqlx_migrations` WHERE success = TRUE";
+
+    let versions = sqlx::query_as::<_, (i64,)>(QUERY)
+        .fetch_all(state.db())
+        .await?;
+    Ok(versions.into_iter().map(|(version,)| version).collect())
+}
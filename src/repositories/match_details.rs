@@ -5,7 +5,8 @@ const TABLE_NAME: &str = "match_detail";
 #[allow(clippy::too_many_arguments)]
 pub async fn create<T: DatabaseState>(
     state: &T,
-    steam_id: &str,
+    player_id: u64,
+    nickname: Option<&str>,
     match_id: u64,
     frags: i16,
     deaths: i16,
@@ -16,20 +17,15 @@ pub async fn create<T: DatabaseState>(
     rating_after_match: f64,
     rating_delta: f64,
 ) -> sqlx::Result<()> {
-    const PLAYER_SELECT_QUERY: &str = "SELECT id FROM `player` WHERE steam_id = ?";
     const INSERT_QUERY: &str = const_str::concat!(
         "INSERT INTO `",
         TABLE_NAME,
-        "` (player_id, match_id, frags, deaths, average_ping, damage_dealt, damage_taken, model, rating_after_match, rating_delta) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        "` (player_id, nickname, match_id, frags, deaths, average_ping, damage_dealt, damage_taken, model, rating_after_match, rating_delta) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
     );
 
-    let (player_id,) = sqlx::query_as::<_, (u64,)>(PLAYER_SELECT_QUERY)
-        .bind(steam_id)
-        .fetch_one(state.db())
-        .await?;
-
     sqlx::query(INSERT_QUERY)
         .bind(player_id)
+        .bind(nickname)
         .bind(match_id)
         .bind(frags)
         .bind(deaths)
@@ -50,7 +46,7 @@ pub async fn fetch_match_details<T: DatabaseState>(
     match_id: u64,
 ) -> sqlx::Result<Vec<MatchDetail>> {
     sqlx::query_as::<_, MatchDetail>(
-        "SELECT m.id, m.player_id, s.steam_name, s.steam_id, s.steam_avatar_url, m.match_id, m.frags, m.deaths, m.average_ping, m.damage_dealt, m.damage_taken, m.model, m.rating_after_match, m.rating_delta
+        "SELECT m.id, m.player_id, m.nickname, s.steam_name, s.steam_id, s.steam_avatar_url, m.match_id, m.frags, m.deaths, m.average_ping, m.damage_dealt, m.damage_taken, m.model, m.rating_after_match, m.rating_delta
          FROM match_detail m
          LEFT JOIN player s ON m.player_id = s.id
          WHERE m.match_id = ?", 
@@ -60,6 +56,32 @@ pub async fn fetch_match_details<T: DatabaseState>(
     .await
 }
 
+pub async fn fetch_many_by_match_ids<T: DatabaseState>(
+    state: &T,
+    match_ids: &[u64],
+) -> sqlx::Result<Vec<MatchDetail>> {
+    if match_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let values = match_ids.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+    let query = format!(
+        "SELECT m.id, m.player_id, m.nickname, s.steam_name, s.steam_id, s.steam_avatar_url, m.match_id, m.frags, m.deaths, m.average_ping, m.damage_dealt, m.damage_taken, m.model, m.rating_after_match, m.rating_delta
+         FROM {} m
+         LEFT JOIN player s ON m.player_id = s.id
+         WHERE m.match_id IN ({})
+         ORDER BY m.match_id, m.id",
+        TABLE_NAME, values
+    );
+
+    let mut sql_query = sqlx::query_as::<_, MatchDetail>(&query);
+    for match_id in match_ids {
+        sql_query = sql_query.bind(match_id);
+    }
+
+    sql_query.fetch_all(state.db()).await
+}
+
 pub async fn update_ratings<T: DatabaseState>(
     state: &T,
     id: u64,
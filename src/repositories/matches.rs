@@ -1,3 +1,6 @@
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, QueryBuilder};
+
 use crate::{common::state::DatabaseState, entities::matches::Match};
 
 const TABLE_NAME: &str = "match";
@@ -60,6 +63,97 @@ pub async fn fetch_matches<T: DatabaseState>(
         .await
 }
 
+#[derive(Default)]
+pub struct MatchFilters<'a> {
+    pub map_name: Option<&'a str>,
+    pub server_ip: Option<&'a str>,
+    pub match_type: Option<&'a str>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub min_team_size: Option<u32>,
+}
+
+pub async fn fetch_recent_matches<T: DatabaseState>(
+    state: &T,
+    filters: &MatchFilters<'_>,
+    page: u32,
+    limit: u32,
+) -> sqlx::Result<Vec<Match>> {
+    const SELECT_QUERY: &str = const_str::concat!(
+        "SELECT m.id, m.server_ip, m.match_date, m.map_name, ",
+        "CAST(CONCAT(COUNT(DISTINCT CASE WHEN md.model = 'blue' THEN md.player_id END), 'vs', ",
+        "COUNT(DISTINCT CASE WHEN md.model = 'red' THEN md.player_id END)) AS CHAR) AS match_type ",
+        "FROM `",
+        TABLE_NAME,
+        "` m ",
+        "JOIN match_detail md ON m.id = md.match_id ",
+        "WHERE m.processed_at IS NOT NULL"
+    );
+    let limit = std::cmp::min(limit, 50);
+    let offset = (page - 1) * limit;
+
+    let mut query = QueryBuilder::<MySql>::new(SELECT_QUERY);
+    if let Some(map_name) = filters.map_name {
+        query.push(" AND m.map_name = ").push_bind(map_name);
+    }
+    if let Some(server_ip) = filters.server_ip {
+        query.push(" AND m.server_ip = ").push_bind(server_ip);
+    }
+    if let Some(from) = filters.from {
+        query.push(" AND m.match_date >= ").push_bind(from);
+    }
+    if let Some(to) = filters.to {
+        query.push(" AND m.match_date <= ").push_bind(to);
+    }
+
+    query.push(" GROUP BY m.id, m.server_ip, m.match_date, m.map_name HAVING 1 = 1");
+    if let Some(match_type) = filters.match_type {
+        query.push(" AND match_type = ").push_bind(match_type);
+    }
+    if let Some(min_team_size) = filters.min_team_size {
+        query
+            .push(
+                " AND LEAST(COUNT(DISTINCT CASE WHEN md.model = 'blue' THEN md.player_id END), \
+                 COUNT(DISTINCT CASE WHEN md.model = 'red' THEN md.player_id END)) >= ",
+            )
+            .push_bind(min_team_size);
+    }
+
+    query
+        .push(" ORDER BY m.id DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    query.build_query_as::<Match>().fetch_all(state.db()).await
+}
+
+// Pages forward through processed matches, oldest first.
+pub async fn fetch_processed_after<T: DatabaseState>(
+    state: &T,
+    after_id: u64,
+    limit: u32,
+) -> sqlx::Result<Vec<Match>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT m.id, m.server_ip, m.match_date, m.map_name, ",
+        "CAST(CONCAT(COUNT(DISTINCT CASE WHEN md.model = 'blue' THEN md.player_id END), 'vs', ",
+        "COUNT(DISTINCT CASE WHEN md.model = 'red' THEN md.player_id END)) AS CHAR) AS match_type ",
+        "FROM `",
+        TABLE_NAME,
+        "` m ",
+        "JOIN match_detail md ON m.id = md.match_id ",
+        "WHERE m.id > ? AND m.processed_at IS NOT NULL ",
+        "GROUP BY m.id, m.server_ip, m.match_date, m.map_name ",
+        "ORDER BY m.id ASC LIMIT ?"
+    );
+
+    sqlx::query_as::<_, Match>(QUERY)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(state.db())
+        .await
+}
+
 pub async fn fetch_match<T: DatabaseState>(state: &T, id: u64) -> sqlx::Result<Match> {
     const QUERY: &str = const_str::concat!(
         "SELECT m.id, m.server_ip, m.match_date, m.map_name, md.frags, md.deaths, md.rating_after_match, md.rating_delta, ",
@@ -79,10 +173,66 @@ pub async fn fetch_match<T: DatabaseState>(state: &T, id: u64) -> sqlx::Result<M
         .await
 }
 
-pub async fn fetch_all_matches<T: DatabaseState>(state: &T) -> sqlx::Result<Vec<Match>> {
-    const QUERY: &str =
-        const_str::concat!("SELECT id, server_ip, map_name FROM `", TABLE_NAME, "`");
+pub async fn exists<T: DatabaseState>(state: &T, id: u64) -> sqlx::Result<bool> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT EXISTS(SELECT 1 FROM `",
+        TABLE_NAME,
+        "` WHERE id = ?)"
+    );
+
+    let (exists,) = sqlx::query_as::<_, (bool,)>(QUERY)
+        .bind(id)
+        .fetch_one(state.db())
+        .await?;
+    Ok(exists)
+}
+
+pub async fn fetch_max_id<T: DatabaseState>(state: &T) -> sqlx::Result<Option<u64>> {
+    const QUERY: &str = const_str::concat!("SELECT MAX(id) FROM `", TABLE_NAME, "`");
+
+    let (max_id,) = sqlx::query_as::<_, (Option<u64>,)>(QUERY)
+        .fetch_one(state.db())
+        .await?;
+    Ok(max_id)
+}
+
+pub async fn fetch_max_processed_id<T: DatabaseState>(state: &T) -> sqlx::Result<Option<u64>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT MAX(id) FROM `",
+        TABLE_NAME,
+        "` WHERE processed_at IS NOT NULL"
+    );
+
+    let (max_id,) = sqlx::query_as::<_, (Option<u64>,)>(QUERY)
+        .fetch_one(state.db())
+        .await?;
+    Ok(max_id)
+}
+
+pub async fn mark_processed<T: DatabaseState>(state: &T, id: u64) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE `",
+        TABLE_NAME,
+        "` SET processed_at = NOW() WHERE id = ?"
+    );
+
+    sqlx::query(QUERY).bind(id).execute(state.db()).await?;
+    Ok(())
+}
+
+pub async fn fetch_batch<T: DatabaseState>(
+    state: &T,
+    after_id: u64,
+    limit: u32,
+) -> sqlx::Result<Vec<Match>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT id, server_ip, map_name FROM `",
+        TABLE_NAME,
+        "` WHERE id > ? ORDER BY id ASC LIMIT ?"
+    );
     sqlx::query_as::<_, Match>(QUERY)
+        .bind(after_id)
+        .bind(limit)
         .fetch_all(state.db())
         .await
 }
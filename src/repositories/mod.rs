@@ -1,4 +1,9 @@
+pub mod aliases;
+pub mod checkpoints;
+pub mod enrichments;
+pub mod health;
 pub mod match_details;
 pub mod matches;
 pub mod players;
 pub mod stats;
+pub mod webhooks;
@@ -1,41 +1,65 @@
+use sqlx::{MySql, QueryBuilder};
+
 use crate::{
     common::state::DatabaseState,
-    entities::players::{Player, PlayerHistoryCapture},
+    entities::{
+        countries::CountrySummary,
+        players::{Player, PlayerHistoryCapture, VersusRecord},
+    },
 };
 
 const TABLE_NAME: &str = "player";
 
+// Inserts the player together with their initial stats. Returns None when a
+// player with the same Steam ID was created concurrently.
 pub async fn create<T: DatabaseState>(
     state: &T,
     steam_id: &str,
     steam_name: &str,
     steam_avatar_url: &str,
     country: &str,
-) -> sqlx::Result<Player> {
+    initial_rating: f64,
+    initial_uncertainty: f64,
+) -> sqlx::Result<Option<Player>> {
     const INSERT_QUERY: &str = const_str::concat!(
         "INSERT INTO `",
         TABLE_NAME,
         "` (steam_id, steam_name, steam_avatar_url, country) VALUES (?, ?, ?, ?)"
     );
+    const STATS_INSERT_QUERY: &str = "INSERT INTO `stats` (player_id, rating, uncertainty, wins, losses, total_frags, total_deaths) VALUES (?, ?, ?, 0, 0, 0, 0)";
     const SELECT_QUERY: &str = const_str::concat!(
         "SELECT id, steam_id, steam_name, steam_avatar_url, country FROM `",
         TABLE_NAME,
         "` WHERE id = ?"
     );
 
-    let player_id = sqlx::query(INSERT_QUERY)
+    let mut tx = state.db().begin().await?;
+    let inserted = sqlx::query(INSERT_QUERY)
         .bind(steam_id)
         .bind(steam_name)
         .bind(steam_avatar_url)
         .bind(country)
-        .execute(state.db())
-        .await?
-        .last_insert_id();
+        .execute(&mut *tx)
+        .await;
+    let player_id = match inserted {
+        Ok(result) => result.last_insert_id(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Ok(None),
+        Err(e) => return Err(e),
+    };
 
-    sqlx::query_as::<_, Player>(SELECT_QUERY)
+    sqlx::query(STATS_INSERT_QUERY)
         .bind(player_id)
-        .fetch_one(state.db())
-        .await
+        .bind(initial_rating)
+        .bind(initial_uncertainty)
+        .execute(&mut *tx)
+        .await?;
+    let player = sqlx::query_as::<_, Player>(SELECT_QUERY)
+        .bind(player_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Some(player))
 }
 
 pub async fn fetch_one_by_id<T: DatabaseState>(state: &T, id: u64) -> sqlx::Result<Player> {
@@ -85,51 +109,114 @@ pub async fn fetch_many_by_ids<T: DatabaseState>(
     sql_query.fetch_all(state.db()).await
 }
 
-pub async fn fetch_all<T: DatabaseState>(state: &T) -> sqlx::Result<Vec<Player>> {
+pub async fn fetch_batch<T: DatabaseState>(
+    state: &T,
+    after_id: u64,
+    limit: u32,
+) -> sqlx::Result<Vec<Player>> {
     const QUERY: &str = const_str::concat!(
         "SELECT id, steam_id, steam_name, steam_avatar_url, country FROM `",
         TABLE_NAME,
-        "`"
+        "` WHERE id > ? ORDER BY id ASC LIMIT ?"
     );
 
     sqlx::query_as::<_, Player>(QUERY)
+        .bind(after_id)
+        .bind(limit)
         .fetch_all(state.db())
         .await
 }
 
-pub async fn fetch_all_with_unknown_country<T: DatabaseState>(
+pub async fn fetch_batch_with_unknown_country<T: DatabaseState>(
     state: &T,
+    after_id: u64,
+    limit: u32,
 ) -> sqlx::Result<Vec<Player>> {
     const QUERY: &str = const_str::concat!(
         "SELECT id, steam_id, steam_name, steam_avatar_url, country FROM `",
         TABLE_NAME,
-        "` WHERE country = 'xx'"
+        "` WHERE country = 'xx' AND id > ? ORDER BY id ASC LIMIT ?"
     );
 
     sqlx::query_as::<_, Player>(QUERY)
+        .bind(after_id)
+        .bind(limit)
         .fetch_all(state.db())
         .await
 }
 
+// An empty `countries` slice leaves the leaderboard unfiltered.
 pub async fn fetch_leaderboard<T: DatabaseState>(
     state: &T,
+    countries: &[&str],
     page: u32,
     limit: u32,
 ) -> sqlx::Result<Vec<Player>> {
-    const QUERY: &str = const_str::concat!(
+    const SELECT_QUERY: &str = const_str::concat!(
         "SELECT p.id, p.steam_id, p.steam_name, p.steam_avatar_url, p.country FROM `",
         TABLE_NAME,
         "` p ",
         "LEFT JOIN stats s ON p.id = s.player_id ",
-        "WHERE (s.wins + s.losses) > 0 ",
-        "ORDER BY s.rating DESC LIMIT ? OFFSET ?"
+        "WHERE (s.wins + s.losses) > 0"
     );
     let limit = std::cmp::min(limit, 50);
     let offset = (page - 1) * limit;
 
+    let mut query = QueryBuilder::<MySql>::new(SELECT_QUERY);
+    if !countries.is_empty() {
+        query.push(" AND p.country IN (");
+        let mut separated = query.separated(", ");
+        for country in countries {
+            separated.push_bind(*country);
+        }
+        separated.push_unseparated(")");
+    }
+
+    query
+        .push(" ORDER BY s.rating DESC, p.id ASC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    query.build_query_as::<Player>().fetch_all(state.db()).await
+}
+
+pub async fn fetch_by_rank_range<T: DatabaseState>(
+    state: &T,
+    first_rank: u32,
+    last_rank: u32,
+) -> sqlx::Result<Vec<Player>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT p.id, p.steam_id, p.steam_name, p.steam_avatar_url, p.country FROM `",
+        TABLE_NAME,
+        "` p ",
+        "JOIN stats s ON p.id = s.player_id ",
+        "WHERE s.leaderboard_rank BETWEEN ? AND ? ",
+        "ORDER BY s.leaderboard_rank ASC"
+    );
+
     sqlx::query_as::<_, Player>(QUERY)
-        .bind(limit)
-        .bind(offset)
+        .bind(first_rank)
+        .bind(last_rank)
+        .fetch_all(state.db())
+        .await
+}
+
+pub async fn fetch_country_summaries<T: DatabaseState>(
+    state: &T,
+) -> sqlx::Result<Vec<CountrySummary>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT p.country, COUNT(*) AS players, ",
+        "COUNT(CASE WHEN (s.wins + s.losses) > 0 THEN 1 END) AS ranked_players, ",
+        "AVG(CASE WHEN (s.wins + s.losses) > 0 THEN s.rating END) AS average_rating FROM `",
+        TABLE_NAME,
+        "` p ",
+        "LEFT JOIN stats s ON p.id = s.player_id ",
+        "GROUP BY p.country ",
+        "ORDER BY players DESC, p.country ASC"
+    );
+
+    sqlx::query_as::<_, CountrySummary>(QUERY)
         .fetch_all(state.db())
         .await
 }
@@ -150,15 +237,87 @@ pub async fn update_country<T: DatabaseState>(
     Ok(())
 }
 
+// The replaced Steam name is recorded in the name history together with the
+// update, so the history never misses a rename.
+pub async fn update_steam_profile<T: DatabaseState>(
+    state: &T,
+    id: u64,
+    steam_name: &str,
+    steam_avatar_url: &str,
+    replaced_steam_name: Option<&str>,
+) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE `",
+        TABLE_NAME,
+        "` SET steam_name = ?, steam_avatar_url = ? WHERE id = ?"
+    );
+    const NAME_HISTORY_INSERT_QUERY: &str =
+        "INSERT INTO `player_name_history` (player_id, steam_name) VALUES (?, ?)";
+
+    let mut tx = state.db().begin().await?;
+    if let Some(replaced_steam_name) = replaced_steam_name {
+        sqlx::query(NAME_HISTORY_INSERT_QUERY)
+            .bind(id)
+            .bind(replaced_steam_name)
+            .execute(&mut *tx)
+            .await?;
+    }
+    sqlx::query(QUERY)
+        .bind(steam_name)
+        .bind(steam_avatar_url)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn fetch_by_steam_name<T: DatabaseState>(
+    state: &T,
+    steam_name: &str,
+) -> sqlx::Result<Vec<Player>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT id, steam_id, steam_name, steam_avatar_url, country FROM `",
+        TABLE_NAME,
+        "` WHERE steam_name = ? ORDER BY id ASC"
+    );
+
+    sqlx::query_as::<_, Player>(QUERY)
+        .bind(steam_name)
+        .fetch_all(state.db())
+        .await
+}
+
+pub async fn fetch_by_alias<T: DatabaseState>(
+    state: &T,
+    nickname: &str,
+) -> sqlx::Result<Vec<Player>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT id, steam_id, steam_name, steam_avatar_url, country FROM `",
+        TABLE_NAME,
+        "` WHERE id IN (SELECT player_id FROM player_alias WHERE nickname = ?) ORDER BY id ASC"
+    );
+
+    sqlx::query_as::<_, Player>(QUERY)
+        .bind(nickname)
+        .fetch_all(state.db())
+        .await
+}
+
 pub async fn search<T: DatabaseState>(state: &T, query: &str) -> sqlx::Result<Vec<Player>> {
     const QUERY: &str = const_str::concat!(
         "SELECT id, steam_id, steam_name, steam_avatar_url, country FROM `",
         TABLE_NAME,
-        "` WHERE steam_id LIKE ? OR steam_name LIKE ? ORDER BY id ASC"
+        "` WHERE steam_id LIKE ? OR steam_name LIKE ? ",
+        "OR id IN (SELECT player_id FROM player_name_history WHERE steam_name LIKE ?) ",
+        "OR id IN (SELECT player_id FROM player_alias WHERE nickname LIKE ?) ",
+        "ORDER BY id ASC"
     );
     let like_query = format!("%{}%", query);
 
     sqlx::query_as::<_, Player>(QUERY)
+        .bind(&like_query)
+        .bind(&like_query)
         .bind(&like_query)
         .bind(&like_query)
         .fetch_all(state.db())
@@ -192,3 +351,34 @@ pub async fn fetch_rating_history<T: DatabaseState>(
         .fetch_all(state.db())
         .await
 }
+
+pub async fn fetch_versus<T: DatabaseState>(
+    state: &T,
+    player_id: u64,
+    opponent_id: u64,
+) -> sqlx::Result<VersusRecord> {
+    const QUERY: &str = "
+        SELECT
+            COUNT(*) AS matches,
+            CAST(COALESCE(SUM(
+                CASE WHEN (a.model = 'blue' AND af.frags > bf.frags)
+                       OR (a.model = 'red' AND af.frags >= bf.frags)
+                THEN 1 ELSE 0 END
+            ), 0) AS SIGNED) AS wins
+        FROM match_detail a
+        JOIN match_detail b ON a.match_id = b.match_id AND a.model <> b.model
+        JOIN (
+            SELECT match_id, model, SUM(frags) AS frags FROM match_detail GROUP BY match_id, model
+        ) af ON af.match_id = a.match_id AND af.model = a.model
+        JOIN (
+            SELECT match_id, model, SUM(frags) AS frags FROM match_detail GROUP BY match_id, model
+        ) bf ON bf.match_id = b.match_id AND bf.model = b.model
+        WHERE a.player_id = ? AND b.player_id = ?
+    ";
+
+    sqlx::query_as::<_, VersusRecord>(QUERY)
+        .bind(player_id)
+        .bind(opponent_id)
+        .fetch_one(state.db())
+        .await
+}
@@ -7,7 +7,7 @@ pub async fn fetch_one_by_player_id<T: DatabaseState>(
     player_id: u64,
 ) -> sqlx::Result<Stats> {
     const QUERY: &str = const_str::concat!(
-        "SELECT player_id, rating, uncertainty, wins, losses, total_frags, total_deaths FROM `",
+        "SELECT player_id, rating, uncertainty, wins, losses, total_frags, total_deaths, leaderboard_rank FROM `",
         TABLE_NAME,
         "` WHERE player_id = ?"
     );
@@ -18,6 +18,32 @@ pub async fn fetch_one_by_player_id<T: DatabaseState>(
         .await
 }
 
+pub async fn fetch_many_by_player_ids<T: DatabaseState>(
+    state: &T,
+    player_ids: &[u64],
+) -> sqlx::Result<Vec<Stats>> {
+    if player_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let values = player_ids
+        .iter()
+        .map(|_| "?")
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = format!(
+        "SELECT player_id, rating, uncertainty, wins, losses, total_frags, total_deaths, leaderboard_rank FROM `{}` WHERE player_id IN ({})",
+        TABLE_NAME, values
+    );
+
+    let mut sql_query = sqlx::query_as::<_, Stats>(&query);
+    for player_id in player_ids {
+        sql_query = sql_query.bind(player_id);
+    }
+
+    sql_query.fetch_all(state.db()).await
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn update_stats<T: DatabaseState>(
     state: &T,
@@ -62,13 +88,54 @@ pub async fn update_stats<T: DatabaseState>(
         .map(|_| ())
 }
 
-pub async fn reset_all_player_stats<T: DatabaseState>(state: &T) -> sqlx::Result<()> {
+pub async fn reset_all_player_stats<T: DatabaseState>(
+    state: &T,
+    rating: f64,
+    uncertainty: f64,
+) -> sqlx::Result<()> {
     const QUERY: &str = const_str::concat!(
         "UPDATE `",
         TABLE_NAME,
-        "` SET rating = 1000, uncertainty = 333.33333, wins = 0, losses = 0, total_frags = 0, total_deaths = 0"
+        "` SET rating = ?, uncertainty = ?, wins = 0, losses = 0, total_frags = 0, total_deaths = 0, ",
+        "leaderboard_rank = NULL"
+    );
+
+    sqlx::query(QUERY)
+        .bind(rating)
+        .bind(uncertainty)
+        .execute(state.db())
+        .await?;
+    Ok(())
+}
+
+pub async fn refresh_ranks<T: DatabaseState>(state: &T) -> sqlx::Result<()> {
+    // Ties are broken by player ID so ranks are unique and match the
+    // leaderboard ordering.
+    const QUERY: &str = const_str::concat!(
+        "UPDATE `",
+        TABLE_NAME,
+        "` s LEFT JOIN (",
+        "SELECT player_id, ROW_NUMBER() OVER (ORDER BY rating DESC, player_id ASC) AS position ",
+        "FROM `",
+        TABLE_NAME,
+        "` WHERE (wins + losses) > 0",
+        ") ranked ON ranked.player_id = s.player_id ",
+        "SET s.leaderboard_rank = ranked.position"
     );
 
     sqlx::query(QUERY).execute(state.db()).await?;
     Ok(())
 }
+
+pub async fn count_ranked<T: DatabaseState>(state: &T) -> sqlx::Result<u32> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT COUNT(*) FROM `",
+        TABLE_NAME,
+        "` WHERE leaderboard_rank IS NOT NULL"
+    );
+
+    let (count,) = sqlx::query_as::<_, (i64,)>(QUERY)
+        .fetch_one(state.db())
+        .await?;
+    Ok(count as u32)
+}
@@ -0,0 +1,101 @@
+use crate::{
+    common::state::DatabaseState,
+    entities::webhooks::{Webhook, WebhookDelivery},
+};
+
+const TABLE_NAME: &str = "webhook";
+const DELIVERY_TABLE_NAME: &str = "webhook_delivery";
+
+pub async fn fetch_active_by_event<T: DatabaseState>(
+    state: &T,
+    event: &str,
+) -> sqlx::Result<Vec<Webhook>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT id, url, secret, events FROM `",
+        TABLE_NAME,
+        "` WHERE active = TRUE AND (events = '*' OR FIND_IN_SET(?, events) > 0)"
+    );
+
+    sqlx::query_as::<_, Webhook>(QUERY)
+        .bind(event)
+        .fetch_all(state.db())
+        .await
+}
+
+pub async fn create_delivery<T: DatabaseState>(
+    state: &T,
+    webhook_id: u64,
+    event: &str,
+    payload: &str,
+) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "INSERT INTO `",
+        DELIVERY_TABLE_NAME,
+        "` (webhook_id, event, payload) VALUES (?, ?, ?)"
+    );
+
+    sqlx::query(QUERY)
+        .bind(webhook_id)
+        .bind(event)
+        .bind(payload)
+        .execute(state.db())
+        .await?;
+
+    Ok(())
+}
+
+pub async fn fetch_due_deliveries<T: DatabaseState>(
+    state: &T,
+    max_attempts: u32,
+    limit: u32,
+) -> sqlx::Result<Vec<WebhookDelivery>> {
+    const QUERY: &str = const_str::concat!(
+        "SELECT d.id, d.webhook_id, w.url, w.secret, d.event, d.payload, d.attempts FROM `",
+        DELIVERY_TABLE_NAME,
+        "` d ",
+        "JOIN `",
+        TABLE_NAME,
+        "` w ON d.webhook_id = w.id ",
+        "WHERE d.delivered_at IS NULL AND d.attempts < ? AND d.next_attempt_at <= NOW() AND w.active = TRUE ",
+        "ORDER BY d.id ASC LIMIT ?"
+    );
+
+    sqlx::query_as::<_, WebhookDelivery>(QUERY)
+        .bind(max_attempts)
+        .bind(limit)
+        .fetch_all(state.db())
+        .await
+}
+
+pub async fn mark_delivered<T: DatabaseState>(state: &T, id: u64) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE `",
+        DELIVERY_TABLE_NAME,
+        "` SET attempts = attempts + 1, delivered_at = NOW(), last_error = NULL WHERE id = ?"
+    );
+
+    sqlx::query(QUERY).bind(id).execute(state.db()).await?;
+    Ok(())
+}
+
+pub async fn mark_failed<T: DatabaseState>(
+    state: &T,
+    id: u64,
+    retry_in_secs: u64,
+    error: &str,
+) -> sqlx::Result<()> {
+    const QUERY: &str = const_str::concat!(
+        "UPDATE `",
+        DELIVERY_TABLE_NAME,
+        "` SET attempts = attempts + 1, next_attempt_at = DATE_ADD(NOW(), INTERVAL ? SECOND), last_error = LEFT(?, 512) ",
+        "WHERE id = ?"
+    );
+
+    sqlx::query(QUERY)
+        .bind(retry_in_secs)
+        .bind(error)
+        .bind(id)
+        .execute(state.db())
+        .await?;
+    Ok(())
+}
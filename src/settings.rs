@@ -1,52 +1,573 @@
+use actix_web::http::{Method, header::HeaderName};
+use anyhow::bail;
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::Url;
+use serde::{Deserialize, Deserializer};
 use tracing::Level;
 
-use crate::common::env::FromEnv;
+use crate::common::{env::EnvOverlay, secret::Secret};
 use std::env;
-use std::ops::Deref;
-use std::sync::LazyLock;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
 use std::time::Duration;
 
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AppSettings {
-    pub app_component: String,
+    pub app_component: Option<String>,
     pub app_port: u16,
 
+    #[serde(deserialize_with = "deserialize_level")]
     pub log_level: Level,
+    #[serde(
+        rename = "shutdown_grace_period_secs",
+        deserialize_with = "deserialize_secs"
+    )]
+    pub shutdown_grace_period: Duration,
+    pub migrate_on_startup: bool,
 
-    pub database_url: String,
+    pub database_url: Secret<String>,
+    #[serde(
+        rename = "database_wait_timeout_secs",
+        deserialize_with = "deserialize_secs"
+    )]
     pub database_wait_timeout: Duration,
     pub database_max_connections: usize,
 
-    pub steam_api_key: String,
+    pub steam_api_key: Option<Secret<String>>,
+    #[serde(
+        rename = "profile_refresh_interval_secs",
+        deserialize_with = "deserialize_secs"
+    )]
+    pub profile_refresh_interval: Duration,
+
+    pub discord_application_id: Option<String>,
+    pub discord_bot_token: Option<Secret<String>>,
+    pub discord_public_key: Option<String>,
+    pub discord_channel_id: Option<String>,
+
+    pub rating: RatingSettings,
+    pub auth: AuthSettings,
+    pub cors: CorsSettings,
+    pub rate_limit: RateLimitSettings,
+    pub cache: CacheSettings,
+    pub services: ServiceSettings,
+    pub legacy_routes: LegacyRoutesSettings,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RatingSettings {
+    pub initial_rating: f64,
+    pub initial_uncertainty: f64,
+    pub beta: f64,
+    pub uncertainty_tolerance: f64,
+    pub blacklisted_maps: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSettings {
+    pub api_key_header: String,
+    pub write_api_keys: Vec<Secret<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsSettings {
+    pub public: CorsPolicySettings,
+    pub write: CorsPolicySettings,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsPolicySettings {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub supports_credentials: bool,
+    pub max_age_secs: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitSettings {
+    pub enabled: bool,
+    pub trust_proxy_headers: bool,
+    pub api_key_header: String,
+    pub api_keys: Vec<Secret<String>>,
+    #[serde(deserialize_with = "deserialize_read_policy")]
+    pub read: RateLimitPolicy,
+    #[serde(deserialize_with = "deserialize_search_policy")]
+    pub search: RateLimitPolicy,
+    #[serde(deserialize_with = "deserialize_write_policy")]
+    pub write: RateLimitPolicy,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitPolicy {
+    pub requests_per_minute: u32,
+    pub burst: u32,
+}
+
+const DEFAULT_READ_POLICY: RateLimitPolicy = RateLimitPolicy {
+    requests_per_minute: 120,
+    burst: 30,
+};
+const DEFAULT_SEARCH_POLICY: RateLimitPolicy = RateLimitPolicy {
+    requests_per_minute: 30,
+    burst: 10,
+};
+const DEFAULT_WRITE_POLICY: RateLimitPolicy = RateLimitPolicy {
+    requests_per_minute: 120,
+    burst: 60,
+};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateLimitPolicyOverride {
+    requests_per_minute: Option<u32>,
+    burst: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSettings {
+    pub backend: CacheBackend,
+    pub redis_url: Option<Secret<String>>,
+    #[serde(rename = "ttl_secs", deserialize_with = "deserialize_secs")]
+    pub ttl: Duration,
+    pub max_entries: usize,
+    pub http_max_age_secs: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBackend {
+    None,
+    Memory,
+    Redis,
+}
+
+impl FromStr for CacheBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(CacheBackend::None),
+            "memory" => Ok(CacheBackend::Memory),
+            "redis" => Ok(CacheBackend::Redis),
+            _ => Err(format!(
+                "unknown cache backend '{s}', expected none, memory or redis"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServiceSettings {
+    pub agdb_api_url: String,
+    pub ip_api_url: String,
+    pub discord_api_url: String,
+    pub steam_api_url: String,
+    #[serde(rename = "request_timeout_secs", deserialize_with = "deserialize_secs")]
+    pub request_timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LegacyRoutesSettings {
+    pub deprecated_at: DateTime<Utc>,
+    pub sunset_at: DateTime<Utc>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            app_component: None,
+            app_port: 7270,
+
+            log_level: Level::INFO,
+            shutdown_grace_period: Duration::from_secs(30),
+            migrate_on_startup: false,
+
+            database_url: Secret::default(),
+            database_wait_timeout: Duration::from_secs(60),
+            database_max_connections: 10,
+
+            steam_api_key: None,
+            profile_refresh_interval: Duration::from_secs(6 * 60 * 60),
+
+            discord_application_id: None,
+            discord_bot_token: None,
+            discord_public_key: None,
+            discord_channel_id: None,
+
+            rating: RatingSettings::default(),
+            auth: AuthSettings::default(),
+            cors: CorsSettings::default(),
+            rate_limit: RateLimitSettings::default(),
+            cache: CacheSettings::default(),
+            services: ServiceSettings::default(),
+            legacy_routes: LegacyRoutesSettings::default(),
+        }
+    }
+}
+
+impl Default for RatingSettings {
+    fn default() -> Self {
+        RatingSettings {
+            initial_rating: 1000.0,
+            initial_uncertainty: 333.33333,
+            beta: 25.0 / 6.0,
+            uncertainty_tolerance: 0.000_001,
+            blacklisted_maps: vec!["bootbox".to_string(), "justwar2".to_string()],
+        }
+    }
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        AuthSettings {
+            api_key_header: "X-Api-Key".to_string(),
+            write_api_keys: Vec::new(),
+        }
+    }
+}
+
+impl Default for CorsSettings {
+    fn default() -> Self {
+        CorsSettings {
+            public: CorsPolicySettings {
+                allowed_origins: vec!["*".to_string()],
+                allowed_methods: vec!["GET".to_string()],
+                ..CorsPolicySettings::default()
+            },
+            write: CorsPolicySettings {
+                allowed_methods: vec!["POST".to_string()],
+                allowed_headers: vec!["Content-Type".to_string(), "X-Api-Key".to_string()],
+                ..CorsPolicySettings::default()
+            },
+        }
+    }
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        RateLimitSettings {
+            enabled: false,
+            trust_proxy_headers: false,
+            api_key_header: "X-Api-Key".to_string(),
+            api_keys: Vec::new(),
+            read: DEFAULT_READ_POLICY,
+            search: DEFAULT_SEARCH_POLICY,
+            write: DEFAULT_WRITE_POLICY,
+        }
+    }
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        CacheSettings {
+            backend: CacheBackend::Memory,
+            redis_url: None,
+            ttl: Duration::from_secs(300),
+            max_entries: 10_000,
+            http_max_age_secs: 30,
+        }
+    }
+}
+
+impl Default for ServiceSettings {
+    fn default() -> Self {
+        ServiceSettings {
+            agdb_api_url: "https://agdb.7mochi.ru".to_string(),
+            ip_api_url: "http://ip-api.com".to_string(),
+            discord_api_url: "https://discord.com/api/v10".to_string(),
+            steam_api_url: "https://api.steampowered.com".to_string(),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Default for LegacyRoutesSettings {
+    fn default() -> Self {
+        LegacyRoutesSettings {
+            deprecated_at: Utc.with_ymd_and_hms(2026, 10, 19, 0, 0, 0).unwrap(),
+            sunset_at: Utc.with_ymd_and_hms(2027, 4, 1, 0, 0, 0).unwrap(),
+        }
+    }
 }
 
 impl AppSettings {
-    pub fn load_from_env() -> anyhow::Result<Self> {
+    // Values come from the defaults, then the optional TOML file, then the environment.
+    pub fn load() -> anyhow::Result<Self> {
         let _ = dotenv::dotenv();
 
-        let app_component = env::var("APP_COMPONENT")?;
-        let app_port = u16::from_env("APP_PORT")?;
+        let mut errors = Vec::new();
+        let mut settings = match Self::load_from_file() {
+            Ok(settings) => settings,
+            Err(e) => {
+                errors.push(format!("{e:#}"));
+                AppSettings::default()
+            }
+        };
+
+        let mut overlay = EnvOverlay::default();
+        settings.apply_env(&mut overlay);
+        errors.extend(overlay.errors);
+        errors.extend(settings.validate());
+
+        if !errors.is_empty() {
+            bail!("Invalid configuration:\n  - {}", errors.join("\n  - "));
+        }
+        Ok(settings)
+    }
+
+    fn load_from_file() -> anyhow::Result<Self> {
+        let path = match env::var("CONFIG_FILE") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => match PathBuf::from(DEFAULT_CONFIG_FILE) {
+                path if path.exists() => path,
+                _ => return Ok(AppSettings::default()),
+            },
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+        toml::from_str(&contents).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))
+    }
+
+    fn apply_env(&mut self, overlay: &mut EnvOverlay) {
+        overlay.set_optional("APP_COMPONENT", &mut self.app_component);
+        overlay.set("APP_PORT", &mut self.app_port);
+
+        overlay.set("LOG_LEVEL", &mut self.log_level);
+        overlay.set_secs(
+            "SHUTDOWN_GRACE_PERIOD_SECS",
+            &mut self.shutdown_grace_period,
+        );
+        overlay.set("MIGRATE_ON_STARTUP", &mut self.migrate_on_startup);
+
+        overlay.set("DATABASE_URL", &mut self.database_url);
+        overlay.set_secs(
+            "DATABASE_WAIT_TIMEOUT_SECS",
+            &mut self.database_wait_timeout,
+        );
+        overlay.set(
+            "DATABASE_MAX_CONNECTIONS",
+            &mut self.database_max_connections,
+        );
+
+        overlay.set_optional("STEAM_API_KEY", &mut self.steam_api_key);
+        overlay.set_secs(
+            "PROFILE_REFRESH_INTERVAL_SECS",
+            &mut self.profile_refresh_interval,
+        );
+
+        overlay.set_optional("DISCORD_APPLICATION_ID", &mut self.discord_application_id);
+        overlay.set_optional("DISCORD_BOT_TOKEN", &mut self.discord_bot_token);
+        overlay.set_optional("DISCORD_PUBLIC_KEY", &mut self.discord_public_key);
+        overlay.set_optional("DISCORD_CHANNEL_ID", &mut self.discord_channel_id);
 
-        let log_level = Level::from_env("LOG_LEVEL")?;
+        let rating = &mut self.rating;
+        overlay.set("RATING_INITIAL_RATING", &mut rating.initial_rating);
+        overlay.set(
+            "RATING_INITIAL_UNCERTAINTY",
+            &mut rating.initial_uncertainty,
+        );
+        overlay.set("RATING_BETA", &mut rating.beta);
+        overlay.set(
+            "RATING_UNCERTAINTY_TOLERANCE",
+            &mut rating.uncertainty_tolerance,
+        );
+        overlay.set_list("RATING_BLACKLISTED_MAPS", &mut rating.blacklisted_maps);
 
-        let database_url = env::var("DATABASE_URL")?;
-        let database_wait_timeout_secs = u64::from_env("DATABASE_WAIT_TIMEOUT_SECS")?;
-        let database_wait_timeout = Duration::from_secs(database_wait_timeout_secs);
-        let database_max_connections = usize::from_env("DATABASE_MAX_CONNECTIONS")?;
+        let auth = &mut self.auth;
+        overlay.set("AUTH_API_KEY_HEADER", &mut auth.api_key_header);
+        let mut write_api_keys = Vec::new();
+        overlay.set_list("AUTH_WRITE_API_KEYS", &mut write_api_keys);
+        if !write_api_keys.is_empty() {
+            auth.write_api_keys = write_api_keys.into_iter().map(Secret::new).collect();
+        }
 
-        let steam_api_key = env::var("STEAM_API_KEY")?;
+        for (prefix, cors) in [
+            ("CORS_PUBLIC", &mut self.cors.public),
+            ("CORS_WRITE", &mut self.cors.write),
+        ] {
+            overlay.set_list(
+                &format!("{prefix}_ALLOWED_ORIGINS"),
+                &mut cors.allowed_origins,
+            );
+            overlay.set_list(
+                &format!("{prefix}_ALLOWED_METHODS"),
+                &mut cors.allowed_methods,
+            );
+            overlay.set_list(
+                &format!("{prefix}_ALLOWED_HEADERS"),
+                &mut cors.allowed_headers,
+            );
+            overlay.set(
+                &format!("{prefix}_SUPPORTS_CREDENTIALS"),
+                &mut cors.supports_credentials,
+            );
+            overlay.set_optional(&format!("{prefix}_MAX_AGE_SECS"), &mut cors.max_age_secs);
+        }
 
-        Ok(AppSettings {
-            app_component,
-            app_port,
+        let rate_limit = &mut self.rate_limit;
+        overlay.set("RATE_LIMIT_ENABLED", &mut rate_limit.enabled);
+        overlay.set(
+            "RATE_LIMIT_TRUST_PROXY_HEADERS",
+            &mut rate_limit.trust_proxy_headers,
+        );
+        overlay.set("RATE_LIMIT_API_KEY_HEADER", &mut rate_limit.api_key_header);
+        let mut api_keys = Vec::new();
+        overlay.set_list("RATE_LIMIT_API_KEYS", &mut api_keys);
+        if !api_keys.is_empty() {
+            rate_limit.api_keys = api_keys.into_iter().map(Secret::new).collect();
+        }
+        for (prefix, policy) in [
+            ("RATE_LIMIT_READ", &mut rate_limit.read),
+            ("RATE_LIMIT_SEARCH", &mut rate_limit.search),
+            ("RATE_LIMIT_WRITE", &mut rate_limit.write),
+        ] {
+            overlay.set(
+                &format!("{prefix}_REQUESTS_PER_MINUTE"),
+                &mut policy.requests_per_minute,
+            );
+            overlay.set(&format!("{prefix}_BURST"), &mut policy.burst);
+        }
 
-            log_level,
+        let cache = &mut self.cache;
+        overlay.set("CACHE_BACKEND", &mut cache.backend);
+        overlay.set_optional("CACHE_REDIS_URL", &mut cache.redis_url);
+        overlay.set_secs("CACHE_TTL_SECS", &mut cache.ttl);
+        overlay.set("CACHE_MAX_ENTRIES", &mut cache.max_entries);
+        overlay.set("CACHE_HTTP_MAX_AGE_SECS", &mut cache.http_max_age_secs);
 
-            database_url,
-            database_wait_timeout,
-            database_max_connections,
+        let services = &mut self.services;
+        overlay.set("AGDB_API_URL", &mut services.agdb_api_url);
+        overlay.set("IP_API_URL", &mut services.ip_api_url);
+        overlay.set("DISCORD_API_URL", &mut services.discord_api_url);
+        overlay.set("STEAM_API_URL", &mut services.steam_api_url);
+        overlay.set_secs(
+            "EXTERNAL_REQUEST_TIMEOUT_SECS",
+            &mut services.request_timeout,
+        );
 
-            steam_api_key,
-        })
+        let legacy_routes = &mut self.legacy_routes;
+        overlay.set(
+            "LEGACY_ROUTES_DEPRECATED_AT",
+            &mut legacy_routes.deprecated_at,
+        );
+        overlay.set("LEGACY_ROUTES_SUNSET_AT", &mut legacy_routes.sunset_at);
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, error: &str| {
+            if !ok {
+                errors.push(error.to_string());
+            }
+        };
+
+        check(
+            !self.database_url.expose().is_empty(),
+            "database_url (DATABASE_URL) is required",
+        );
+        check(
+            self.database_max_connections > 0,
+            "database_max_connections (DATABASE_MAX_CONNECTIONS) must be greater than 0",
+        );
+
+        check(
+            !self.profile_refresh_interval.is_zero(),
+            "profile_refresh_interval_secs (PROFILE_REFRESH_INTERVAL_SECS) must be greater than 0",
+        );
+
+        check(
+            self.rating.initial_uncertainty > 0.0,
+            "rating.initial_uncertainty must be greater than 0",
+        );
+        check(self.rating.beta > 0.0, "rating.beta must be greater than 0");
+        check(
+            self.rating.uncertainty_tolerance >= 0.0,
+            "rating.uncertainty_tolerance must not be negative",
+        );
+
+        for (name, cors) in [("public", &self.cors.public), ("write", &self.cors.write)] {
+            for method in &cors.allowed_methods {
+                check(
+                    Method::from_str(method).is_ok(),
+                    &format!("cors.{name}.allowed_methods contains an invalid method: {method}"),
+                );
+            }
+            check(
+                !(cors.supports_credentials && cors.allowed_origins.iter().any(|o| o == "*")),
+                &format!("cors.{name} cannot support credentials with a wildcard origin"),
+            );
+        }
+
+        check(
+            HeaderName::from_str(&self.auth.api_key_header).is_ok(),
+            "auth.api_key_header is not a valid header name",
+        );
+        check(
+            HeaderName::from_str(&self.rate_limit.api_key_header).is_ok(),
+            "rate_limit.api_key_header is not a valid header name",
+        );
+        for (name, policy) in [
+            ("read", &self.rate_limit.read),
+            ("search", &self.rate_limit.search),
+            ("write", &self.rate_limit.write),
+        ] {
+            check(
+                policy.requests_per_minute > 0,
+                &format!("rate_limit.{name}.requests_per_minute must be greater than 0"),
+            );
+            check(
+                policy.burst > 0,
+                &format!("rate_limit.{name}.burst must be greater than 0"),
+            );
+        }
+
+        check(
+            self.cache.max_entries > 0,
+            "cache.max_entries must be greater than 0",
+        );
+        if self.cache.backend == CacheBackend::Redis {
+            check(
+                cfg!(feature = "redis"),
+                "cache.backend is redis but the binary was built without the redis feature",
+            );
+            check(
+                self.cache.redis_url.is_some(),
+                "cache.redis_url (CACHE_REDIS_URL) is required for the redis backend",
+            );
+        }
+
+        for (name, url) in [
+            ("services.agdb_api_url", &self.services.agdb_api_url),
+            ("services.ip_api_url", &self.services.ip_api_url),
+            ("services.discord_api_url", &self.services.discord_api_url),
+            ("services.steam_api_url", &self.services.steam_api_url),
+        ] {
+            check(
+                Url::parse(url).is_ok(),
+                &format!("{name} is not a valid URL: {url}"),
+            );
+        }
+
+        check(
+            self.legacy_routes.sunset_at > self.legacy_routes.deprecated_at,
+            "legacy_routes.sunset_at must be later than legacy_routes.deprecated_at",
+        );
+
+        errors
     }
 
     pub fn get() -> &'static AppSettings {
@@ -54,8 +575,126 @@ impl AppSettings {
     }
 }
 
+static SETTINGS: OnceLock<AppSettings> = OnceLock::new();
+
+pub fn init() -> anyhow::Result<&'static AppSettings> {
+    if let Some(settings) = SETTINGS.get() {
+        return Ok(settings);
+    }
+    let settings = AppSettings::load()?;
+    Ok(SETTINGS.get_or_init(|| settings))
+}
+
 pub fn settings() -> &'static AppSettings {
-    static SETTINGS: LazyLock<AppSettings> =
-        LazyLock::new(|| AppSettings::load_from_env().expect("Failed to load settings"));
-    SETTINGS.deref()
+    SETTINGS.get_or_init(|| AppSettings::load().expect("Failed to load settings"))
+}
+
+fn deserialize_level<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Level, D::Error> {
+    let level = String::deserialize(deserializer)?;
+    Level::from_str(&level).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
+
+// Fields left out of a rate limit table keep that group's default.
+fn deserialize_policy<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    defaults: RateLimitPolicy,
+) -> Result<RateLimitPolicy, D::Error> {
+    let policy = RateLimitPolicyOverride::deserialize(deserializer)?;
+    Ok(RateLimitPolicy {
+        requests_per_minute: policy
+            .requests_per_minute
+            .unwrap_or(defaults.requests_per_minute),
+        burst: policy.burst.unwrap_or(defaults.burst),
+    })
+}
+
+fn deserialize_read_policy<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<RateLimitPolicy, D::Error> {
+    deserialize_policy(deserializer, DEFAULT_READ_POLICY)
+}
+
+fn deserialize_search_policy<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<RateLimitPolicy, D::Error> {
+    deserialize_policy(deserializer, DEFAULT_SEARCH_POLICY)
+}
+
+fn deserialize_write_policy<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<RateLimitPolicy, D::Error> {
+    deserialize_policy(deserializer, DEFAULT_WRITE_POLICY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_rate_limit_policy_keeps_group_defaults() {
+        let settings: AppSettings = toml::from_str(
+            r#"
+            [rate_limit.search]
+            requests_per_minute = 10
+
+            [rate_limit.write]
+            burst = 5
+            "#,
+        )
+        .unwrap();
+
+        let rate_limit = settings.rate_limit;
+        assert_eq!(rate_limit.read.requests_per_minute, 120);
+        assert_eq!(rate_limit.read.burst, 30);
+        assert_eq!(rate_limit.search.requests_per_minute, 10);
+        assert_eq!(rate_limit.search.burst, 10);
+        assert_eq!(rate_limit.write.requests_per_minute, 120);
+        assert_eq!(rate_limit.write.burst, 5);
+    }
+
+    #[test]
+    fn example_config_is_valid() {
+        let settings: AppSettings = toml::from_str(include_str!("../config.example.toml")).unwrap();
+
+        assert_eq!(settings.validate(), Vec::<String>::new());
+        assert_eq!(
+            settings.legacy_routes.sunset_at,
+            Utc.with_ymd_and_hms(2027, 4, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn sunset_must_follow_deprecation() {
+        let settings: AppSettings = toml::from_str(
+            r#"
+            [legacy_routes]
+            deprecated_at = "2027-04-01T00:00:00Z"
+            sunset_at = "2026-10-19T00:00:00Z"
+            "#,
+        )
+        .unwrap();
+
+        assert!(
+            settings
+                .validate()
+                .iter()
+                .any(|error| error.starts_with("legacy_routes.sunset_at"))
+        );
+    }
+
+    #[test]
+    fn rate_limit_policy_rejects_unknown_fields() {
+        let result = toml::from_str::<AppSettings>(
+            r#"
+            [rate_limit.read]
+            requests_per_second = 2
+            "#,
+        );
+
+        assert!(result.is_err());
+    }
 }
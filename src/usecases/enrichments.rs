@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use steamid_ng::SteamID;
+
+use crate::{
+    adapters::steam_api::{self, PlayerSummary},
+    common::{
+        error::ServiceResult,
+        state::{CacheState, DatabaseState},
+    },
+    repositories::{enrichments, players},
+};
+
+const MAX_ENRICHMENT_ATTEMPTS: u32 = 20;
+const MAX_RETRY_DELAY_SECS: u64 = 6 * 3600;
+
+fn retry_delay_secs(attempts: u32) -> u64 {
+    60u64
+        .saturating_mul(2u64.saturating_pow(attempts))
+        .min(MAX_RETRY_DELAY_SECS)
+}
+
+pub async fn enqueue<T: DatabaseState>(state: &T, player_id: u64) -> ServiceResult<()> {
+    enrichments::create(state, player_id).await?;
+    Ok(())
+}
+
+// Fills in the Steam name and avatar of players created while Steam was unavailable.
+pub async fn enrich_due<T: DatabaseState + CacheState>(state: &T) -> ServiceResult<()> {
+    let pending = enrichments::fetch_due(
+        state,
+        MAX_ENRICHMENT_ATTEMPTS,
+        steam_api::MAX_SUMMARIES_PER_REQUEST,
+    )
+    .await?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let steam_ids: Vec<u64> = pending
+        .iter()
+        .filter_map(|enrichment| SteamID::from_steam2(&enrichment.steam_id).ok())
+        .map(u64::from)
+        .collect();
+    let mut summaries: HashMap<u64, PlayerSummary> =
+        match steam_api::get_player_summaries(&steam_ids).await {
+            Ok(summaries) => summaries
+                .into_iter()
+                .filter_map(|summary| Some((summary.steamid.parse().ok()?, summary)))
+                .collect(),
+            Err(e) => {
+                for enrichment in &pending {
+                    let retry_in = retry_delay_secs(enrichment.attempts);
+                    warn!(
+                        player_id = enrichment.player_id,
+                        attempt = enrichment.attempts + 1,
+                        "Steam enrichment failed, retrying in {retry_in}s: {e}"
+                    );
+                    enrichments::mark_failed(state, enrichment.player_id, retry_in, &e.to_string())
+                        .await?;
+                }
+                return Ok(());
+            }
+        };
+
+    let mut enriched = 0;
+    for enrichment in &pending {
+        let summary = SteamID::from_steam2(&enrichment.steam_id)
+            .ok()
+            .and_then(|steam_id| summaries.remove(&u64::from(steam_id)));
+        match summary {
+            Some(summary) => {
+                info!(
+                    "Enriched Steam profile for player ID: {} ({})",
+                    enrichment.player_id, summary.personaname
+                );
+                players::update_steam_profile(
+                    state,
+                    enrichment.player_id,
+                    &summary.personaname,
+                    &summary.avatarfull,
+                    None,
+                )
+                .await?;
+                enriched += 1;
+            }
+            None => warn!(
+                "Steam has no profile for player ID: {} ({}), giving up on enrichment",
+                enrichment.player_id, enrichment.steam_id
+            ),
+        }
+        enrichments::delete(state, enrichment.player_id).await?;
+    }
+
+    if enriched > 0 {
+        state.cache().invalidate_all().await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay_secs(0), 60);
+        assert_eq!(retry_delay_secs(1), 120);
+        assert_eq!(retry_delay_secs(5), 1920);
+        assert_eq!(retry_delay_secs(9), MAX_RETRY_DELAY_SECS);
+        assert_eq!(retry_delay_secs(u32::MAX), MAX_RETRY_DELAY_SECS);
+    }
+}
@@ -0,0 +1,16 @@
+use crate::{
+    common::{
+        error::ServiceResult,
+        events::Event,
+        state::{DatabaseState, EventState},
+    },
+    usecases::webhooks,
+};
+
+// Webhook deliveries are stored before the event is broadcast, so they are not
+// lost when a subscriber lags behind or the process exits right after.
+pub async fn publish<T: DatabaseState + EventState>(state: &T, event: Event) -> ServiceResult<()> {
+    webhooks::enqueue_event(state, &event).await?;
+    state.events().publish(event);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::{collections::HashSet, time::Instant};
+
+use crate::{
+    common::state::DatabaseState,
+    lifecycle::MIGRATOR,
+    models::health::{DatabaseHealth, MigrationsHealth, PendingMigration, Readiness},
+    repositories::health,
+};
+
+pub async fn check_migrations<T: DatabaseState>(state: &T) -> sqlx::Result<MigrationsHealth> {
+    let mut applied = health::fetch_applied_migrations(state).await?;
+    applied.sort_unstable();
+    let applied_versions: HashSet<i64> = applied.iter().copied().collect();
+
+    let pending: Vec<PendingMigration> = MIGRATOR
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .filter(|migration| !applied_versions.contains(&migration.version))
+        .map(|migration| PendingMigration {
+            version: migration.version,
+            description: migration.description.to_string(),
+        })
+        .collect();
+
+    Ok(MigrationsHealth {
+        up_to_date: pending.is_empty(),
+        applied,
+        pending,
+    })
+}
+
+pub async fn check_readiness<T: DatabaseState>(state: &T, shutting_down: bool) -> Readiness {
+    let started_at = Instant::now();
+    let database = match health::ping(state).await {
+        Ok(()) => DatabaseHealth {
+            reachable: true,
+            latency_ms: Some(started_at.elapsed().as_millis()),
+            error: None,
+        },
+        Err(e) => {
+            warn!("Readiness check failed to reach the database: {e}");
+            DatabaseHealth {
+                reachable: false,
+                latency_ms: None,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+
+    let migrations = match database.reachable {
+        true => check_migrations(state).await.unwrap_or_else(|e| {
+            warn!("Readiness check failed to read applied migrations: {e}");
+            MigrationsHealth {
+                up_to_date: false,
+                applied: Vec::new(),
+                pending: Vec::new(),
+            }
+        }),
+        false => MigrationsHealth {
+            up_to_date: false,
+            applied: Vec::new(),
+            pending: Vec::new(),
+        },
+    };
+
+    Readiness {
+        ready: !shutting_down && database.reachable && migrations.up_to_date,
+        shutting_down,
+        database,
+        migrations,
+    }
+}
@@ -3,25 +3,27 @@ use skillratings::{
     Outcomes,
     weng_lin::{WengLinConfig, WengLinRating, weng_lin_two_teams},
 };
-use std::{collections::HashSet, sync::LazyLock};
+use std::collections::HashMap;
 
 use crate::{
     api::match_details::RequestBody,
     common::{
-        error::{AppError, ServiceResult},
-        state::DatabaseState,
+        error::{AppError, FieldError, ServiceResult, unexpected},
+        events::Event,
+        metrics,
+        state::{CacheState, DatabaseState, EventState},
     },
-    entities::match_details::MatchDetail,
+    entities::{match_details::MatchDetail, matches::Match, stats::Stats as StatsEntity},
+    models::events::{LeaderboardLeaderChanged, MatchProcessed, PlayerRatingChange},
     repositories::{
-        match_details, matches,
+        aliases, match_details, matches,
         players::{self},
         stats,
     },
+    settings::AppSettings,
+    usecases::{self, events, steam_ids},
 };
 
-static BLACKLISTED_MAPS: LazyLock<HashSet<&'static str>> =
-    LazyLock::new(|| HashSet::from(["bootbox", "justwar2"]));
-
 pub struct PlayerRating {
     rating: WengLinRating,
     detail: MatchDetail,
@@ -46,6 +48,34 @@ pub fn validate_teams(details: &Json<Vec<RequestBody>>) -> ServiceResult<()> {
     Ok(())
 }
 
+pub async fn validate_match<T: DatabaseState>(
+    state: &T,
+    details: &Json<Vec<RequestBody>>,
+) -> ServiceResult<()> {
+    let match_id = details[0].match_id;
+    let mut field_errors: Vec<FieldError> = details
+        .iter()
+        .enumerate()
+        .filter(|(_, detail)| detail.match_id != match_id)
+        .map(|(index, _)| FieldError {
+            field: format!("[{index}].match_id"),
+            reason: "must be the same for every entry".to_string(),
+        })
+        .collect();
+
+    if !matches::exists(state, match_id).await? {
+        field_errors.push(FieldError {
+            field: "[0].match_id".to_string(),
+            reason: "match does not exist".to_string(),
+        });
+    }
+
+    match field_errors.is_empty() {
+        true => Ok(()),
+        false => Err(AppError::Validation(field_errors)),
+    }
+}
+
 pub fn determine_winner(blue_stats: &[i16], red_stats: &[i16]) -> Outcomes {
     let blue_score: i16 = blue_stats.iter().sum();
     let red_score: i16 = red_stats.iter().sum();
@@ -57,16 +87,32 @@ pub fn determine_winner(blue_stats: &[i16], red_stats: &[i16]) -> Outcomes {
     }
 }
 
-pub async fn create_match_details<T: DatabaseState>(
+pub async fn create_match_details<T: DatabaseState + EventState + CacheState>(
     state: &T,
     details: &Json<Vec<RequestBody>>,
 ) -> ServiceResult<()> {
     validate_teams(details)?;
+    validate_match(state, details).await?;
 
+    // Players are resolved up front so an unknown Steam ID fails before any detail is stored.
+    let mut player_ids = Vec::with_capacity(details.len());
     for detail in details.iter() {
+        let steam_id = steam_ids::normalize(&detail.steam_id).await?;
+        let player = usecases::players::find_or_create_player(
+            state,
+            &steam_id,
+            detail.nickname.as_deref(),
+            None,
+        )
+        .await?;
+        player_ids.push(player.id);
+    }
+
+    for (detail, player_id) in details.iter().zip(player_ids) {
         match_details::create(
             state,
-            &detail.steam_id,
+            player_id,
+            detail.nickname.as_deref(),
             detail.match_id,
             detail.frags,
             detail.deaths,
@@ -78,11 +124,92 @@ pub async fn create_match_details<T: DatabaseState>(
             0.0,
         )
         .await?;
+        if let Some(nickname) = detail.nickname.as_deref() {
+            aliases::upsert(state, player_id, nickname).await?;
+        }
     }
+
+    metrics::MATCHES_INGESTED_TOTAL.inc();
     Ok(())
 }
 
-pub async fn process_match<T: DatabaseState>(state: &T, match_id: u64) -> ServiceResult<()> {
+async fn publish_match_processed<T: DatabaseState + EventState>(
+    state: &T,
+    a_match: Match,
+    match_details: &[MatchDetail],
+    ratings: &HashMap<u64, (f64, f64)>,
+) -> ServiceResult<()> {
+    let players = match_details
+        .iter()
+        .map(|detail| {
+            let (rating_after_match, rating_delta) =
+                ratings.get(&detail.player_id).copied().unwrap_or_default();
+            PlayerRatingChange {
+                player_id: detail.player_id,
+                steam_id: detail.steam_id.clone(),
+                steam_name: detail.steam_name.clone(),
+                nickname: detail.nickname.clone(),
+                model: detail.model.clone(),
+                frags: detail.frags,
+                deaths: detail.deaths,
+                rating_after_match,
+                rating_delta,
+            }
+        })
+        .collect();
+
+    events::publish(
+        state,
+        Event::MatchProcessed(MatchProcessed {
+            id: a_match.id,
+            server_ip: a_match.server_ip,
+            match_date: a_match.match_date,
+            map_name: a_match.map_name,
+            match_type: a_match.match_type,
+            players,
+        }),
+    )
+    .await
+}
+
+async fn fetch_leader_id<T: DatabaseState>(state: &T) -> ServiceResult<Option<u64>> {
+    let leaderboard = players::fetch_leaderboard(state, &[], 1, 1).await?;
+    Ok(leaderboard.first().map(|player| player.id))
+}
+
+async fn publish_leader_change<T: DatabaseState + EventState>(
+    state: &T,
+    previous_leader_id: Option<u64>,
+) -> ServiceResult<()> {
+    let Some(leader_id) = fetch_leader_id(state).await? else {
+        return Ok(());
+    };
+    if previous_leader_id == Some(leader_id) {
+        return Ok(());
+    }
+
+    let leader = players::fetch_one_by_id(state, leader_id).await?;
+    let leader_stats = stats::fetch_one_by_player_id(state, leader_id).await?;
+    events::publish(
+        state,
+        Event::LeaderboardLeaderChanged(LeaderboardLeaderChanged {
+            player_id: leader.id,
+            steam_id: leader.steam_id,
+            steam_name: leader.steam_name,
+            rating: leader_stats.rating,
+            previous_player_id: previous_leader_id,
+        }),
+    )
+    .await
+}
+
+// Replays skip events and webhooks, subscribers already saw those matches live.
+// Ranks are refreshed and the cache is cleared once when the replay stops.
+pub async fn process_match<T: DatabaseState + EventState + CacheState>(
+    state: &T,
+    match_id: u64,
+    replaying: bool,
+) -> ServiceResult<()> {
     let match_details = match_details::fetch_match_details(state, match_id).await?;
 
     if match_details.is_empty() {
@@ -99,13 +226,21 @@ pub async fn process_match<T: DatabaseState>(state: &T, match_id: u64) -> Servic
         .iter()
         .map(|detail| detail.player_id)
         .collect();
-    let players = players::fetch_many_by_ids(state, player_ids).await?;
+    let players = players::fetch_many_by_ids(state, player_ids.clone()).await?;
+    let stats_by_player: HashMap<u64, StatsEntity> =
+        stats::fetch_many_by_player_ids(state, &player_ids)
+            .await?
+            .into_iter()
+            .map(|stats| (stats.player_id, stats))
+            .collect();
 
     let mut player_ratings: Vec<PlayerRating> = Vec::new();
 
     for player in &players {
         if let Some(detail) = match_details.iter().find(|d| d.player_id == player.id) {
-            let stats = stats::fetch_one_by_player_id(state, player.id).await?;
+            let Some(stats) = stats_by_player.get(&player.id) else {
+                return unexpected(anyhow::anyhow!("Player ID: {} has no stats", player.id));
+            };
 
             player_ratings.push(PlayerRating {
                 rating: WengLinRating {
@@ -117,17 +252,22 @@ pub async fn process_match<T: DatabaseState>(state: &T, match_id: u64) -> Servic
         }
     }
 
-    if BLACKLISTED_MAPS.contains(a_match.map_name.as_str()) {
+    let rating_settings = &AppSettings::get().rating;
+    if rating_settings.blacklisted_maps.contains(&a_match.map_name) {
         info!(
             "Match on blacklisted map '{}' (ID: {}), preserving original ratings.",
             a_match.map_name, match_id
         );
+        metrics::MATCHES_SKIPPED_BLACKLISTED_TOTAL.inc();
+
+        let mut preserved_ratings = HashMap::new();
 
         for detail in match_details.iter() {
             let player_rating = player_ratings
                 .iter()
                 .find(|pr| pr.detail.player_id == detail.player_id)
                 .unwrap();
+            preserved_ratings.insert(detail.player_id, (player_rating.rating.rating, 0.0));
 
             stats::update_stats(
                 state,
@@ -144,9 +284,20 @@ pub async fn process_match<T: DatabaseState>(state: &T, match_id: u64) -> Servic
             match_details::update_ratings(state, detail.id, player_rating.rating.rating, 0.0)
                 .await?;
         }
+
+        matches::mark_processed(state, match_id).await?;
+        if !replaying {
+            state.cache().invalidate_all().await;
+            publish_match_processed(state, a_match, &match_details, &preserved_ratings).await?;
+        }
         return Ok(());
     }
 
+    let previous_leader_id = match replaying {
+        true => None,
+        false => fetch_leader_id(state).await?,
+    };
+
     let (blue_team_players, red_team_players): (Vec<&PlayerRating>, Vec<&PlayerRating>) =
         player_ratings
             .iter()
@@ -158,7 +309,10 @@ pub async fn process_match<T: DatabaseState>(state: &T, match_id: u64) -> Servic
     let red_team_ratings: Vec<WengLinRating> =
         red_team_players.iter().map(|pr| pr.rating).collect();
 
-    let config = WengLinConfig::new();
+    let config = WengLinConfig {
+        beta: rating_settings.beta,
+        uncertainty_tolerance: rating_settings.uncertainty_tolerance,
+    };
 
     let outcome = determine_winner(
         &match_details
@@ -176,7 +330,7 @@ pub async fn process_match<T: DatabaseState>(state: &T, match_id: u64) -> Servic
     let (new_blue_ratings, new_red_ratings) =
         weng_lin_two_teams(&blue_team_ratings, &red_team_ratings, &outcome, &config);
 
-    let mut updated_ratings = std::collections::HashMap::new();
+    let mut updated_ratings = HashMap::new();
 
     blue_team_players
         .iter()
@@ -221,5 +375,19 @@ pub async fn process_match<T: DatabaseState>(state: &T, match_id: u64) -> Servic
         match_details::update_ratings(state, detail.id, new_rating.rating, *rating_delta).await?;
     }
 
+    let published_ratings = updated_ratings
+        .iter()
+        .map(|(player_id, (new_rating, rating_delta))| {
+            (*player_id, (new_rating.rating, *rating_delta))
+        })
+        .collect();
+    matches::mark_processed(state, match_id).await?;
+    if !replaying {
+        stats::refresh_ranks(state).await?;
+        state.cache().invalidate_all().await;
+        publish_match_processed(state, a_match, &match_details, &published_ratings).await?;
+        publish_leader_change(state, previous_leader_id).await?;
+    }
+
     Ok(())
 }
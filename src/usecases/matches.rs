@@ -1,12 +1,27 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
 use crate::{
     common::{
         error::{AppError, ServiceResult, unexpected},
-        state::DatabaseState,
+        state::{CacheState, DatabaseState},
     },
+    entities::{match_details::MatchDetail as MatchDetailEntity, matches::Match as MatchEntity},
     models::matches::{Match, MatchExtended},
     repositories::{match_details, matches},
 };
 
+#[derive(Default)]
+pub struct MatchFilters<'a> {
+    pub map_name: Option<&'a str>,
+    pub server_ip: Option<&'a str>,
+    pub match_type: Option<&'a str>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub min_team_size: Option<u32>,
+}
+
 pub async fn create_match<T: DatabaseState>(
     state: &T,
     server_ip: &str,
@@ -16,22 +31,90 @@ pub async fn create_match<T: DatabaseState>(
     Ok(Match::from(new_match))
 }
 
-pub async fn fetch_match<T: DatabaseState>(state: &T, id: u64) -> ServiceResult<MatchExtended> {
-    let existing_match = match matches::fetch_match(state, id).await {
-        Ok(a_match) => a_match,
-        Err(sqlx::Error::RowNotFound) => return Err(AppError::MatchNotFound),
+pub async fn fetch_match<T: DatabaseState + CacheState>(
+    state: &T,
+    id: u64,
+) -> ServiceResult<MatchExtended> {
+    let key = format!("match:{id}");
+    state
+        .cache()
+        .get_or_load(&key, || async {
+            let existing_match = match matches::fetch_match(state, id).await {
+                Ok(a_match) => a_match,
+                Err(sqlx::Error::RowNotFound) => return Err(AppError::MatchNotFound),
+                Err(e) => return unexpected(e),
+            };
+
+            let existing_match_details =
+                match match_details::fetch_match_details(state, existing_match.id).await {
+                    Ok(details) => details,
+                    Err(sqlx::Error::RowNotFound) => return Err(AppError::MatchDetailNotFound),
+                    Err(e) => return unexpected(e),
+                };
+
+            Ok(MatchExtended::from((
+                existing_match,
+                existing_match_details,
+            )))
+        })
+        .await
+}
+
+pub async fn fetch_matches<T: DatabaseState>(
+    state: &T,
+    filters: &MatchFilters<'_>,
+    page: u32,
+    limit: u32,
+) -> ServiceResult<Vec<MatchExtended>> {
+    let filters = matches::MatchFilters {
+        map_name: filters.map_name,
+        server_ip: filters.server_ip,
+        match_type: filters.match_type,
+        from: filters.from,
+        to: filters.to,
+        min_team_size: filters.min_team_size,
+    };
+    let existing_matches = match matches::fetch_recent_matches(state, &filters, page, limit).await {
+        Ok(matches) => matches,
         Err(e) => return unexpected(e),
     };
 
-    let existing_match_details =
-        match match_details::fetch_match_details(state, existing_match.id).await {
-            Ok(details) => details,
-            Err(sqlx::Error::RowNotFound) => return Err(AppError::MatchDetailNotFound),
-            Err(e) => return unexpected(e),
-        };
-
-    Ok(MatchExtended::from((
-        existing_match,
-        existing_match_details,
-    )))
+    with_match_details(state, existing_matches).await
+}
+
+pub async fn fetch_processed_matches<T: DatabaseState>(
+    state: &T,
+    after_id: u64,
+    limit: u32,
+) -> ServiceResult<Vec<MatchExtended>> {
+    let processed_matches = matches::fetch_processed_after(state, after_id, limit).await?;
+    with_match_details(state, processed_matches).await
+}
+
+pub async fn fetch_last_processed_id<T: DatabaseState>(state: &T) -> ServiceResult<Option<u64>> {
+    Ok(matches::fetch_max_processed_id(state).await?)
+}
+
+pub async fn with_match_details<T: DatabaseState>(
+    state: &T,
+    existing_matches: Vec<MatchEntity>,
+) -> ServiceResult<Vec<MatchExtended>> {
+    let match_ids: Vec<u64> = existing_matches.iter().map(|m| m.id).collect();
+    let mut details_by_match: HashMap<u64, Vec<MatchDetailEntity>> = HashMap::new();
+    for detail in match_details::fetch_many_by_match_ids(state, &match_ids).await? {
+        details_by_match
+            .entry(detail.match_id)
+            .or_default()
+            .push(detail);
+    }
+
+    Ok(existing_matches
+        .into_iter()
+        .map(|existing_match| {
+            let existing_match_details = details_by_match
+                .remove(&existing_match.id)
+                .unwrap_or_default();
+            MatchExtended::from((existing_match, existing_match_details))
+        })
+        .collect())
 }
@@ -1,4 +1,9 @@
+pub mod enrichments;
+pub mod events;
+pub mod health;
 pub mod location;
 pub mod match_details;
 pub mod matches;
 pub mod players;
+pub mod steam_ids;
+pub mod webhooks;
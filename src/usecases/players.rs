@@ -1,17 +1,24 @@
-use std::net::IpAddr;
+use std::{collections::HashMap, net::IpAddr};
 
-use steam_api_client::{Player as SteamPlayer, SteamClient};
 use steamid_ng::SteamID;
 
 use crate::{
+    adapters::steam_api,
     common::{
         error::{AppError, ServiceResult, unexpected},
-        state::DatabaseState,
+        events::Event,
+        metrics,
+        state::{CacheState, DatabaseState, EventState},
+    },
+    entities::{
+        players::{Player as PlayerEntity, PlayerHistory as PlayerHistoryEntity},
+        stats::Stats as StatsEntity,
     },
-    entities::players::PlayerHistory as PlayerHistoryEntity,
     models::{
+        countries::{CountrySummary, Region},
+        events::PlayerCreated,
         matches::MatchExtended,
-        players::{Player, PlayerHistory},
+        players::{LeaderboardPage, Player, PlayerHistory, Versus},
     },
     repositories::{
         matches,
@@ -19,14 +26,28 @@ use crate::{
         stats,
     },
     settings::AppSettings,
-    usecases::location,
+    usecases::{enrichments, events, location, steam_ids},
 };
 
-pub async fn create_player<T: DatabaseState>(
+pub async fn create_player<T: DatabaseState + EventState + CacheState>(
     state: &T,
     steam_id: &str,
+    nickname: Option<&str>,
     ip_address: IpAddr,
 ) -> ServiceResult<Player> {
+    let steam_id = steam_ids::normalize(steam_id).await?;
+    let player = find_or_create_player(state, &steam_id, nickname, Some(ip_address)).await?;
+    Ok(Player::from(player))
+}
+
+// Expects a Steam ID already normalised by `steam_ids::normalize`. Without an IP
+// address the country is left unknown for the countries backfill to fill in.
+pub async fn find_or_create_player<T: DatabaseState + EventState>(
+    state: &T,
+    steam_id: &str,
+    nickname: Option<&str>,
+    ip_address: Option<IpAddr>,
+) -> ServiceResult<PlayerEntity> {
     let settings = AppSettings::get();
     let steam_id_2 = match SteamID::from_steam2(steam_id) {
         Ok(id) => id,
@@ -34,47 +55,97 @@ pub async fn create_player<T: DatabaseState>(
             return Err(AppError::PlayerSteamIDInvalid);
         }
     };
-    let client = SteamClient::new(settings.steam_api_key.to_string());
 
-    let steam_response = client
-        .get_player_summaries(vec![u64::from(steam_id_2).to_string()])
-        .await?;
+    match players::fetch_one_by_steamid(state, steam_id).await {
+        Ok(player) => return Ok(player),
+        Err(sqlx::Error::RowNotFound) => {}
+        Err(e) => return unexpected(e),
+    }
 
-    let player_steam_info: &SteamPlayer = match steam_response.playersummaries.players.first() {
-        Some(player) => player,
-        None => {
-            return Err(AppError::PlayerSteamDoesNotExist);
-        }
-    };
+    // Players are still created while Steam is unavailable, with a placeholder
+    // profile that is filled in later by the enrichment worker.
+    let (steam_name, steam_avatar_url, needs_enrichment) =
+        match steam_api::get_player_summaries(&[u64::from(steam_id_2)]).await {
+            Ok(summaries) => match summaries.into_iter().next() {
+                Some(summary) => (summary.personaname, summary.avatarfull, false),
+                None => return Err(AppError::PlayerSteamDoesNotExist),
+            },
+            Err(e) => {
+                warn!("Steam is unavailable, creating {steam_id} with a placeholder profile: {e}");
+                (
+                    nickname.unwrap_or(steam_id).to_string(),
+                    steam_api::DEFAULT_AVATAR_URL.to_string(),
+                    true,
+                )
+            }
+        };
 
-    let player = match players::fetch_one_by_steamid(state, steam_id).await {
-        Ok(player) => player,
-        Err(_e) => {
-            let location_info = location::get_location(ip_address).await;
-            let created_player = players::create(
-                state,
-                steam_id,
-                &player_steam_info.personaname,
-                &player_steam_info.avatarfull,
-                &location_info.country,
-            )
-            .await?;
-            stats::update_stats(state, created_player.id, 1000.0, 333.33333, 0, 0, 0, 0)
-                .await?;
-            return Ok(Player::from(created_player));
-        }
+    let country = match ip_address {
+        Some(ip_address) => location::get_location(ip_address).await.country,
+        None => "xx".to_string(),
     };
-    Ok(Player::from(player))
+    let created_player = match players::create(
+        state,
+        steam_id,
+        &steam_name,
+        &steam_avatar_url,
+        &country,
+        settings.rating.initial_rating,
+        settings.rating.initial_uncertainty,
+    )
+    .await?
+    {
+        Some(player) => player,
+        // Another ingestion created the player between the lookup and the insert.
+        None => return Ok(players::fetch_one_by_steamid(state, steam_id).await?),
+    };
+    if needs_enrichment {
+        enrichments::enqueue(state, created_player.id).await?;
+    }
+    metrics::PLAYERS_CREATED_TOTAL.inc();
+    events::publish(
+        state,
+        Event::PlayerCreated(PlayerCreated {
+            id: created_player.id,
+            steam_id: created_player.steam_id.clone(),
+            steam_name: created_player.steam_name.clone(),
+            steam_avatar_url: created_player.steam_avatar_url.clone(),
+            country: created_player.country.clone(),
+        }),
+    )
+    .await?;
+    Ok(created_player)
 }
 
-pub async fn fetch_player<T: DatabaseState>(state: &T, id: u64) -> ServiceResult<Player> {
-    match players::fetch_one_by_id(state, id).await {
-        Ok(mut player) => {
-            let stats = stats::fetch_one_by_player_id(state, id).await?;
-            player.stats = stats;
+pub async fn fetch_player<T: DatabaseState + CacheState>(
+    state: &T,
+    id: u64,
+) -> ServiceResult<Player> {
+    let key = format!("player:{id}");
+    state
+        .cache()
+        .get_or_load(&key, || async {
+            match players::fetch_one_by_id(state, id).await {
+                Ok(mut player) => {
+                    let stats = stats::fetch_one_by_player_id(state, id).await?;
+                    player.stats = stats;
 
-            Ok(Player::from(player))
-        }
+                    Ok(Player::from(player))
+                }
+                Err(sqlx::Error::RowNotFound) => Err(AppError::PlayerNotFound),
+                Err(e) => unexpected(e),
+            }
+        })
+        .await
+}
+
+pub async fn fetch_player_by_steam_id<T: DatabaseState + CacheState>(
+    state: &T,
+    steam_id: &str,
+) -> ServiceResult<Player> {
+    let steam_id = steam_ids::normalize(steam_id).await?;
+    match players::fetch_one_by_steamid(state, &steam_id).await {
+        Ok(player) => fetch_player(state, player.id).await,
         Err(sqlx::Error::RowNotFound) => Err(AppError::PlayerNotFound),
         Err(e) => unexpected(e),
     }
@@ -92,6 +163,29 @@ pub async fn search_players<T: DatabaseState>(
     Ok(players.into_iter().map(Player::from).collect())
 }
 
+// Exact matches win over partial ones, from the most to the least specific: the
+// Steam ID, the current Steam name, an in-game nickname, then the search.
+pub async fn find_players<T: DatabaseState>(state: &T, value: &str) -> ServiceResult<Vec<Player>> {
+    if let Ok(steam_id) = steam_ids::normalize(value).await {
+        match players::fetch_one_by_steamid(state, &steam_id).await {
+            Ok(player) => return Ok(vec![Player::from(player)]),
+            Err(sqlx::Error::RowNotFound) => {}
+            Err(e) => return unexpected(e),
+        }
+    }
+
+    let by_steam_name = players::fetch_by_steam_name(state, value).await?;
+    if !by_steam_name.is_empty() {
+        return Ok(by_steam_name.into_iter().map(Player::from).collect());
+    }
+    let by_alias = players::fetch_by_alias(state, value).await?;
+    if !by_alias.is_empty() {
+        return Ok(by_alias.into_iter().map(Player::from).collect());
+    }
+
+    search_players(state, value).await
+}
+
 pub async fn fetch_player_matches<T: DatabaseState>(
     state: &T,
     id: u64,
@@ -104,32 +198,101 @@ pub async fn fetch_player_matches<T: DatabaseState>(
         Err(e) => return unexpected(e),
     };
 
-    let mut result_matches: Vec<MatchExtended> = Vec::new();
+    crate::usecases::matches::with_match_details(state, existing_matches).await
+}
 
-    for existing_match in existing_matches {
-        let existing_match_details =
-            crate::repositories::match_details::fetch_match_details(state, existing_match.id)
-                .await?;
+pub async fn fetch_leaderboard<T: DatabaseState + CacheState>(
+    state: &T,
+    country: Option<&str>,
+    region: Option<Region>,
+    page: u32,
+    limit: u32,
+) -> ServiceResult<Vec<Player>> {
+    let countries = match (country, region) {
+        (Some(country), Some(region)) if !region.countries().contains(&country) => {
+            return Ok(Vec::new());
+        }
+        (Some(country), _) => vec![country],
+        (None, Some(region)) => region.countries().to_vec(),
+        (None, None) => Vec::new(),
+    };
 
-        let match_data = MatchExtended::from((existing_match, existing_match_details));
-        result_matches.push(match_data);
-    }
+    let key = format!(
+        "leaderboard:{}:{}:{page}:{limit}",
+        country.unwrap_or("*"),
+        region.map_or("*", |region| region.as_str())
+    );
+    state
+        .cache()
+        .get_or_load(&key, || async {
+            let leaderboard = players::fetch_leaderboard(state, &countries, page, limit).await?;
+            with_stats(state, leaderboard).await
+        })
+        .await
+}
 
-    Ok(result_matches)
+pub async fn fetch_country_summaries<T: DatabaseState + CacheState>(
+    state: &T,
+) -> ServiceResult<Vec<CountrySummary>> {
+    state
+        .cache()
+        .get_or_load("countries", || async {
+            let summaries = players::fetch_country_summaries(state).await?;
+            Ok(summaries.into_iter().map(CountrySummary::from).collect())
+        })
+        .await
 }
 
-pub async fn fetch_leaderboard<T: DatabaseState>(
+pub async fn fetch_leaderboard_around<T: DatabaseState + CacheState>(
     state: &T,
-    page: u32,
+    id: u64,
     limit: u32,
-) -> ServiceResult<Vec<Player>> {
-    let mut leaderboard = players::fetch_leaderboard(state, page, limit).await?;
-    for player in &mut leaderboard {
-        let stats = stats::fetch_one_by_player_id(state, player.id).await?;
+) -> ServiceResult<LeaderboardPage> {
+    let key = format!("leaderboard_around:{id}:{limit}");
+    state
+        .cache()
+        .get_or_load(&key, || async {
+            let player_stats = match stats::fetch_one_by_player_id(state, id).await {
+                Ok(stats) => stats,
+                Err(sqlx::Error::RowNotFound) => return Err(AppError::PlayerNotFound),
+                Err(e) => return unexpected(e),
+            };
+            let Some(rank) = player_stats.leaderboard_rank else {
+                return Err(AppError::PlayerNotRanked);
+            };
 
-        player.stats = stats;
+            let total_ranked = stats::count_ranked(state).await?;
+            // Keep the player centred, shifting the window when they are
+            // close to either end of the leaderboard.
+            let last_rank = total_ranked
+                .min(rank + limit / 2)
+                .max(limit.min(total_ranked));
+            let first_rank = last_rank.saturating_sub(limit - 1).max(1);
+
+            let neighbours = players::fetch_by_rank_range(state, first_rank, last_rank).await?;
+            Ok(LeaderboardPage {
+                total_ranked,
+                players: with_stats(state, neighbours).await?,
+            })
+        })
+        .await
+}
+
+async fn with_stats<T: DatabaseState>(
+    state: &T,
+    mut players: Vec<PlayerEntity>,
+) -> ServiceResult<Vec<Player>> {
+    let player_ids: Vec<u64> = players.iter().map(|player| player.id).collect();
+    let mut stats_by_player: HashMap<u64, StatsEntity> =
+        stats::fetch_many_by_player_ids(state, &player_ids)
+            .await?
+            .into_iter()
+            .map(|stats| (stats.player_id, stats))
+            .collect();
+    for player in &mut players {
+        player.stats = stats_by_player.remove(&player.id).unwrap_or_default();
     }
-    Ok(leaderboard.into_iter().map(Player::from).collect())
+    Ok(players.into_iter().map(Player::from).collect())
 }
 
 pub async fn fetch_rating_history<T: DatabaseState>(
@@ -141,3 +304,90 @@ pub async fn fetch_rating_history<T: DatabaseState>(
         captures: history,
     }))
 }
+
+pub async fn fetch_versus<T: DatabaseState + CacheState>(
+    state: &T,
+    player_id: u64,
+    opponent_id: u64,
+) -> ServiceResult<Versus> {
+    let player = fetch_player(state, player_id).await?;
+    let opponent = fetch_player(state, opponent_id).await?;
+    let record = players::fetch_versus(state, player_id, opponent_id).await?;
+
+    let matches = record.matches as u32;
+    let wins = record.wins as u32;
+    Ok(Versus {
+        player,
+        opponent,
+        matches,
+        wins,
+        losses: matches - wins,
+    })
+}
+
+// Returns how many of the given players had a changed name or avatar.
+pub async fn refresh_steam_profiles<T: DatabaseState>(
+    state: &T,
+    batch: &[PlayerEntity],
+    dry_run: bool,
+) -> ServiceResult<usize> {
+    let players_by_steam_id: HashMap<u64, &PlayerEntity> = batch
+        .iter()
+        .filter_map(|player| match SteamID::from_steam2(&player.steam_id) {
+            Ok(steam_id) => Some((u64::from(steam_id), player)),
+            Err(_e) => {
+                warn!(
+                    "Skipping player ID: {} with invalid Steam ID: {}",
+                    player.id, player.steam_id
+                );
+                None
+            }
+        })
+        .collect();
+    if players_by_steam_id.is_empty() {
+        return Ok(0);
+    }
+
+    let steam_ids: Vec<u64> = players_by_steam_id.keys().copied().collect();
+    let summaries = steam_api::get_player_summaries(&steam_ids).await?;
+
+    let mut updated = 0;
+    for summary in summaries {
+        let Some(player) = summary
+            .steamid
+            .parse::<u64>()
+            .ok()
+            .and_then(|steam_id| players_by_steam_id.get(&steam_id))
+        else {
+            continue;
+        };
+        let name_changed = summary.personaname != player.steam_name;
+        if !name_changed && summary.avatarfull == player.steam_avatar_url {
+            continue;
+        }
+        updated += 1;
+
+        if dry_run {
+            info!(
+                "Would refresh Steam profile for player ID: {} ({} -> {})",
+                player.id, player.steam_name, summary.personaname
+            );
+            continue;
+        }
+
+        info!(
+            "Refreshing Steam profile for player ID: {} ({} -> {})",
+            player.id, player.steam_name, summary.personaname
+        );
+        players::update_steam_profile(
+            state,
+            player.id,
+            &summary.personaname,
+            &summary.avatarfull,
+            name_changed.then_some(player.steam_name.as_str()),
+        )
+        .await?;
+    }
+
+    Ok(updated)
+}
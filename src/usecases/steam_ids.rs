@@ -0,0 +1,169 @@
+use steamid_ng::{AccountType, SteamID};
+
+use crate::{
+    adapters::steam_api,
+    common::error::{AppError, ServiceResult},
+};
+
+const PROFILES_PREFIX: &str = "steamcommunity.com/profiles/";
+const VANITY_PREFIX: &str = "steamcommunity.com/id/";
+
+enum SteamIdInput<'a> {
+    Id(SteamID),
+    Vanity(&'a str),
+}
+
+// Accepts Steam2 (STEAM_0:1:123), Steam3 ([U:1:246]), SteamID64 and
+// steamcommunity.com profile or vanity URLs.
+fn parse(input: &str) -> Option<SteamIdInput<'_>> {
+    let input = input.trim();
+    if input.starts_with("STEAM_") {
+        return SteamID::from_steam2(input).ok().map(SteamIdInput::Id);
+    }
+    if input.starts_with('[') {
+        return SteamID::from_steam3(input).ok().map(SteamIdInput::Id);
+    }
+    if let Ok(steam_id_64) = input.parse::<u64>() {
+        return SteamID::from_steam64(steam_id_64)
+            .ok()
+            .map(SteamIdInput::Id);
+    }
+
+    let path = input
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("www.")
+        .trim_end_matches('/');
+    if let Some(steam_id_64) = path.strip_prefix(PROFILES_PREFIX) {
+        return steam_id_64
+            .parse::<u64>()
+            .ok()
+            .and_then(|steam_id_64| SteamID::from_steam64(steam_id_64).ok())
+            .map(SteamIdInput::Id);
+    }
+    path.strip_prefix(VANITY_PREFIX)
+        .filter(|vanity| !vanity.is_empty() && !vanity.contains('/'))
+        .map(SteamIdInput::Vanity)
+}
+
+// Half-Life servers report universe 0, which is how players have always been stored.
+fn canonical(steam_id: SteamID) -> ServiceResult<String> {
+    if steam_id.account_type() != AccountType::Individual {
+        return Err(AppError::PlayerSteamIDInvalid);
+    }
+    let account_id = steam_id.account_id();
+    Ok(format!("STEAM_0:{}:{}", account_id & 1, account_id >> 1))
+}
+
+pub async fn normalize(input: &str) -> ServiceResult<String> {
+    match parse(input) {
+        Some(SteamIdInput::Id(steam_id)) => canonical(steam_id),
+        Some(SteamIdInput::Vanity(vanity)) => match steam_api::resolve_vanity_url(vanity).await? {
+            Some(steam_id_64) => SteamID::from_steam64(steam_id_64)
+                .map_err(|_e| AppError::PlayerSteamIDInvalid)
+                .and_then(canonical),
+            None => Err(AppError::PlayerSteamDoesNotExist),
+        },
+        None => Err(AppError::PlayerSteamIDInvalid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_canonical(input: &str) -> Option<String> {
+        match parse(input)? {
+            SteamIdInput::Id(steam_id) => canonical(steam_id).ok(),
+            SteamIdInput::Vanity(_) => None,
+        }
+    }
+
+    #[test]
+    fn ids_are_canonicalized_to_steam2_universe_0() {
+        let cases = [
+            ("STEAM_0:0:123", "STEAM_0:0:123"),
+            ("STEAM_0:1:123", "STEAM_0:1:123"),
+            ("STEAM_1:1:123", "STEAM_0:1:123"),
+            (" STEAM_0:1:123 ", "STEAM_0:1:123"),
+            ("[U:1:246]", "STEAM_0:0:123"),
+            ("[U:1:247]", "STEAM_0:1:123"),
+            ("76561197960265974", "STEAM_0:0:123"),
+            ("76561197960265975", "STEAM_0:1:123"),
+            (
+                "https://steamcommunity.com/profiles/76561197960265974/",
+                "STEAM_0:0:123",
+            ),
+            (
+                "http://www.steamcommunity.com/profiles/76561197960265975",
+                "STEAM_0:1:123",
+            ),
+            (
+                "steamcommunity.com/profiles/76561197960265974",
+                "STEAM_0:0:123",
+            ),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse_canonical(input).as_deref(), Some(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn vanity_urls_are_left_for_steam_to_resolve() {
+        let cases = [
+            (
+                "https://steamcommunity.com/id/gabelogannewell/",
+                "gabelogannewell",
+            ),
+            (
+                "http://www.steamcommunity.com/id/gabelogannewell",
+                "gabelogannewell",
+            ),
+            ("steamcommunity.com/id/gabelogannewell", "gabelogannewell"),
+        ];
+
+        for (input, expected) in cases {
+            match parse(input) {
+                Some(SteamIdInput::Vanity(vanity)) => assert_eq!(vanity, expected, "{input}"),
+                _ => panic!("{input} was not parsed as a vanity URL"),
+            }
+        }
+    }
+
+    #[test]
+    fn non_individual_accounts_are_rejected() {
+        let cases = [
+            "[g:1:4]",
+            "103582791429521412",
+            "https://steamcommunity.com/profiles/103582791429521412",
+        ];
+
+        for input in cases {
+            match parse(input) {
+                Some(SteamIdInput::Id(steam_id)) => {
+                    assert!(canonical(steam_id).is_err(), "{input}")
+                }
+                _ => panic!("{input} was not parsed as a Steam ID"),
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        let cases = [
+            "",
+            "player",
+            "STEAM_0:2",
+            "[U:1]",
+            "steamcommunity.com/profiles/player",
+            "steamcommunity.com/id/",
+            "steamcommunity.com/id/gabelogannewell/games",
+            "example.com/id/gabelogannewell",
+        ];
+
+        for input in cases {
+            assert!(parse(input).is_none(), "{input}");
+        }
+    }
+}
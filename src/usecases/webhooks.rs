@@ -0,0 +1,82 @@
+use crate::{
+    adapters::webhook,
+    common::{
+        error::{ServiceResult, unexpected},
+        events::Event,
+        state::DatabaseState,
+    },
+    repositories::webhooks,
+};
+
+const MAX_DELIVERY_ATTEMPTS: u32 = 10;
+const DELIVERY_BATCH_SIZE: u32 = 50;
+const MAX_RETRY_DELAY_SECS: u64 = 3600;
+
+fn retry_delay_secs(attempts: u32) -> u64 {
+    30u64
+        .saturating_mul(2u64.saturating_pow(attempts))
+        .min(MAX_RETRY_DELAY_SECS)
+}
+
+pub async fn enqueue_event<T: DatabaseState>(state: &T, event: &Event) -> ServiceResult<()> {
+    let subscribers = webhooks::fetch_active_by_event(state, event.name()).await?;
+    if subscribers.is_empty() {
+        return Ok(());
+    }
+
+    let payload = match serde_json::to_string(event) {
+        Ok(payload) => payload,
+        Err(e) => return unexpected(e),
+    };
+
+    for subscriber in subscribers {
+        webhooks::create_delivery(state, subscriber.id, event.name(), &payload).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn deliver_due<T: DatabaseState>(state: &T) -> ServiceResult<()> {
+    let deliveries =
+        webhooks::fetch_due_deliveries(state, MAX_DELIVERY_ATTEMPTS, DELIVERY_BATCH_SIZE).await?;
+
+    for delivery in deliveries {
+        match webhook::deliver(
+            &delivery.url,
+            &delivery.secret,
+            delivery.id,
+            &delivery.event,
+            &delivery.payload,
+        )
+        .await
+        {
+            Ok(()) => webhooks::mark_delivered(state, delivery.id).await?,
+            Err(e) => {
+                let retry_in = retry_delay_secs(delivery.attempts);
+                warn!(
+                    webhook_id = delivery.webhook_id,
+                    delivery_id = delivery.id,
+                    attempt = delivery.attempts + 1,
+                    "Webhook delivery failed, retrying in {retry_in}s: {e:#}"
+                );
+                webhooks::mark_failed(state, delivery.id, retry_in, &format!("{e:#}")).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay_secs(0), 30);
+        assert_eq!(retry_delay_secs(1), 60);
+        assert_eq!(retry_delay_secs(4), 480);
+        assert_eq!(retry_delay_secs(7), MAX_RETRY_DELAY_SECS);
+        assert_eq!(retry_delay_secs(u32::MAX), MAX_RETRY_DELAY_SECS);
+    }
+}
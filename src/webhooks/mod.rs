@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use actix_web::rt::{self, time::sleep};
+
+use crate::{common::state::State, usecases::webhooks};
+
+const DELIVERY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// Deliveries are stored by `usecases::events::publish`, this loop only sends
+// the pending ones, including those left over from before a restart.
+pub fn spawn_dispatcher(state: State) {
+    rt::spawn(async move {
+        while !state.shutdown.is_triggered() {
+            if let Err(e) = webhooks::deliver_due(&state).await {
+                error!("Failed to deliver pending webhooks: {e}");
+            }
+            sleep(DELIVERY_POLL_INTERVAL).await;
+        }
+    });
+}
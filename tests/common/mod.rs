@@ -0,0 +1,213 @@
+// Shared by every test binary, each of which only uses part of it.
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    sync::{Arc, Mutex, OnceLock, mpsc},
+    thread,
+};
+
+use actix_web::{
+    App, HttpRequest, HttpResponse, HttpServer,
+    http::StatusCode,
+    rt::System,
+    web::{self, Bytes, Data},
+};
+use shion::{
+    common::{cache::Cache, events::EventBus, shutdown::Shutdown, state::State},
+    entities::players::Player,
+    lifecycle,
+    repositories::players,
+    settings::{self, AppSettings},
+};
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard};
+
+#[derive(Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+type QueuedResponses = HashMap<String, VecDeque<(u16, String)>>;
+
+#[derive(Clone, Default)]
+struct StubState {
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    responses: Arc<Mutex<QueuedResponses>>,
+}
+
+// A local HTTP server standing in for Steam, Discord and webhook endpoints. It
+// records every request and answers with the responses queued for its path,
+// or an empty JSON object once the queue is empty.
+pub struct Stub {
+    pub url: String,
+    state: StubState,
+}
+
+impl Stub {
+    pub fn start() -> Self {
+        let state = StubState::default();
+        let server_state = state.clone();
+        let (sender, receiver) = mpsc::channel::<SocketAddr>();
+
+        // The server gets its own runtime so it outlives the test that started it.
+        thread::spawn(move || {
+            System::new().block_on(async move {
+                let server = HttpServer::new(move || {
+                    App::new()
+                        .app_data(Data::new(server_state.clone()))
+                        .default_service(web::to(respond))
+                })
+                .workers(1)
+                .disable_signals()
+                .bind(("127.0.0.1", 0))
+                .expect("Failed to bind the stub server");
+                sender.send(server.addrs()[0]).unwrap();
+                server.run().await
+            })
+        });
+
+        let address = receiver.recv().expect("The stub server did not start");
+        Self {
+            url: format!("http://{address}"),
+            state,
+        }
+    }
+
+    pub fn respond(&self, path: &str, status: u16, body: &str) {
+        self.state
+            .responses
+            .lock()
+            .unwrap()
+            .entry(path.to_string())
+            .or_default()
+            .push_back((status, body.to_string()));
+    }
+
+    pub fn requests(&self, path: &str) -> Vec<RecordedRequest> {
+        self.state
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.path == path)
+            .cloned()
+            .collect()
+    }
+
+    pub fn reset(&self) {
+        self.state.requests.lock().unwrap().clear();
+        self.state.responses.lock().unwrap().clear();
+    }
+}
+
+async fn respond(request: HttpRequest, body: Bytes, state: Data<StubState>) -> HttpResponse {
+    let path = request.path().to_string();
+    state.requests.lock().unwrap().push(RecordedRequest {
+        method: request.method().to_string(),
+        path: path.clone(),
+        query: request.query_string().to_string(),
+        headers: request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    value.to_str().unwrap_or_default().to_string(),
+                )
+            })
+            .collect(),
+        body: String::from_utf8_lossy(&body).into_owned(),
+    });
+
+    let (status, body) = state
+        .responses
+        .lock()
+        .unwrap()
+        .get_mut(&path)
+        .and_then(VecDeque::pop_front)
+        .unwrap_or((200, "{}".to_string()));
+    HttpResponse::build(StatusCode::from_u16(status).unwrap())
+        .content_type("application/json")
+        .body(body)
+}
+
+static SERVICES: OnceLock<Stub> = OnceLock::new();
+static SERVICES_LOCK: AsyncMutex<()> = AsyncMutex::const_new(());
+
+// Settings are loaded once per test binary, so every external service points at
+// the same stub.
+fn configure() -> &'static Stub {
+    SERVICES.get_or_init(|| {
+        let stub = Stub::start();
+        // SAFETY: runs once, before the settings are read by any test.
+        unsafe {
+            if std::env::var("DATABASE_URL").is_err() {
+                std::env::set_var("DATABASE_URL", "mysql://root@127.0.0.1:3306/shion_test");
+            }
+            std::env::set_var("STEAM_API_KEY", "test");
+            for service in [
+                "AGDB_API_URL",
+                "IP_API_URL",
+                "DISCORD_API_URL",
+                "STEAM_API_URL",
+            ] {
+                std::env::set_var(service, &stub.url);
+            }
+        }
+        settings::init().expect("Failed to load test settings");
+        stub
+    })
+}
+
+// Tests that queue responses on the shared stub hold the returned guard.
+pub async fn services() -> (&'static Stub, MutexGuard<'static, ()>) {
+    let stub = configure();
+    let guard = SERVICES_LOCK.lock().await;
+    stub.reset();
+    (stub, guard)
+}
+
+pub async fn database() -> State {
+    configure();
+    let settings = AppSettings::get();
+    let db = lifecycle::initialize_db(settings)
+        .await
+        .expect("Failed to connect to the test database");
+    lifecycle::MIGRATOR
+        .run(&db)
+        .await
+        .expect("Failed to migrate the test database");
+
+    State {
+        db,
+        events: EventBus::new(),
+        shutdown: Shutdown::new(),
+        cache: Cache::new(&settings.cache).await.unwrap(),
+    }
+}
+
+pub fn unique_steam_id() -> String {
+    let account_id = uuid::Uuid::new_v4().as_u128() as u32 >> 1;
+    format!("STEAM_0:{}:{}", account_id & 1, account_id >> 1)
+}
+
+pub async fn insert_player(state: &State) -> Player {
+    let settings = AppSettings::get();
+    players::create(
+        state,
+        &unique_steam_id(),
+        "player",
+        "",
+        "xx",
+        settings.rating.initial_rating,
+        settings.rating.initial_uncertainty,
+    )
+    .await
+    .unwrap()
+    .expect("Steam IDs are unique")
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use shion::{
+    adapters::discord_api,
+    common::state::State,
+    discord_bot,
+    repositories::{match_details, matches},
+};
+
+#[actix_web::test]
+async fn messages_are_posted_to_the_channel_with_the_bot_token() {
+    let (stub, _guard) = common::services().await;
+
+    discord_api::create_message("42", "bot-token", "Blue wins")
+        .await
+        .unwrap();
+
+    let requests = stub.requests("/channels/42/messages");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].headers["authorization"], "Bot bot-token");
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(body["content"], "Blue wins");
+}
+
+#[actix_web::test]
+async fn error_responses_fail_the_message() {
+    let (stub, _guard) = common::services().await;
+    stub.respond("/channels/42/messages", 429, "{}");
+
+    let result = discord_api::create_message("42", "bot-token", "Blue wins").await;
+
+    assert!(result.is_err());
+}
+
+async fn insert_match(state: &State, map_name: &str, processed: bool) -> u64 {
+    let a_match = matches::create(state, "127.0.0.1:27015", map_name)
+        .await
+        .unwrap();
+    for model in ["blue", "red"] {
+        let player = common::insert_player(state).await;
+        match_details::create(
+            state, player.id, None, a_match.id, 10, 5, 30, 100, 100, model, 1000.0, 0.0,
+        )
+        .await
+        .unwrap();
+    }
+    if processed {
+        matches::mark_processed(state, a_match.id).await.unwrap();
+    }
+    a_match.id
+}
+
+#[actix_web::test]
+#[ignore = "needs a MySQL database in DATABASE_URL"]
+async fn announcements_page_through_every_processed_match() {
+    let (stub, _guard) = common::services().await;
+    let state = common::database().await;
+    let map_name = format!("announced_{}", uuid::Uuid::new_v4().simple());
+    let pending_map_name = format!("pending_{}", uuid::Uuid::new_v4().simple());
+
+    let start_id = matches::fetch_max_id(&state).await.unwrap().unwrap_or(0);
+    let mut last_id = 0;
+    // More than one page, with a match still waiting to be processed in between.
+    for index in 0..120 {
+        last_id = insert_match(&state, &map_name, true).await;
+        if index == 60 {
+            insert_match(&state, &pending_map_name, false).await;
+        }
+    }
+
+    let announced_id = discord_bot::announce_new_matches(&state, "42", "bot-token", start_id).await;
+
+    assert!(announced_id >= last_id);
+    let messages: Vec<String> = stub
+        .requests("/channels/42/messages")
+        .into_iter()
+        .map(|request| request.body)
+        .collect();
+    assert_eq!(
+        messages
+            .iter()
+            .filter(|message| message.contains(&map_name))
+            .count(),
+        120
+    );
+    assert!(
+        !messages
+            .iter()
+            .any(|message| message.contains(&pending_map_name))
+    );
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use serde_json::json;
+use shion::{
+    adapters::steam_api,
+    common::{error::AppError, state::State},
+    repositories::{enrichments, players},
+    usecases::{self, players::find_or_create_player},
+};
+use steamid_ng::SteamID;
+
+const SUMMARIES_PATH: &str = "/ISteamUser/GetPlayerSummaries/v0002/";
+
+fn steam_id_64(steam_id: &str) -> u64 {
+    u64::from(SteamID::from_steam2(steam_id).unwrap())
+}
+
+fn summaries(steam_id: u64, name: &str) -> String {
+    json!({
+        "response": {
+            "players": [
+                { "steamid": steam_id.to_string(), "personaname": name, "avatarfull": "https://avatars.example/full.jpg" }
+            ]
+        }
+    })
+    .to_string()
+}
+
+// Returns the attempts and whether the enrichment is backing off, or None once it is done.
+async fn enrichment_state(state: &State, player_id: u64) -> Option<(u32, i64)> {
+    sqlx::query_as(
+        "SELECT attempts, CAST(next_attempt_at > NOW() + INTERVAL 50 SECOND AS SIGNED) \
+         FROM `player_enrichment` WHERE player_id = ?",
+    )
+    .bind(player_id)
+    .fetch_optional(&state.db)
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+async fn summaries_are_fetched_for_the_requested_players() {
+    let (stub, _guard) = common::services().await;
+    stub.respond(SUMMARIES_PATH, 200, &summaries(76561197960287930, "Hamlet"));
+
+    let found = steam_api::get_player_summaries(&[76561197960287930])
+        .await
+        .unwrap();
+
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].personaname, "Hamlet");
+    let requests = stub.requests(SUMMARIES_PATH);
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].query.contains("steamids=76561197960287930"));
+    assert!(requests[0].query.contains("key=test"));
+}
+
+#[actix_web::test]
+async fn server_errors_are_reported() {
+    let (stub, _guard) = common::services().await;
+    stub.respond(SUMMARIES_PATH, 503, "{}");
+
+    assert!(
+        steam_api::get_player_summaries(&[76561197960287930])
+            .await
+            .is_err()
+    );
+}
+
+#[actix_web::test]
+#[ignore = "needs a MySQL database in DATABASE_URL"]
+async fn players_get_a_placeholder_profile_while_steam_fails() {
+    let (stub, _guard) = common::services().await;
+    let state = common::database().await;
+    let steam_id = common::unique_steam_id();
+    stub.respond(SUMMARIES_PATH, 502, "{}");
+
+    let player = find_or_create_player(&state, &steam_id, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(player.steam_name, steam_id);
+    assert_eq!(player.steam_avatar_url, steam_api::DEFAULT_AVATAR_URL);
+    assert_eq!(enrichment_state(&state, player.id).await, Some((0, 0)));
+}
+
+#[actix_web::test]
+#[ignore = "needs a MySQL database in DATABASE_URL"]
+async fn rate_limited_players_fall_back_to_their_nickname() {
+    let (stub, _guard) = common::services().await;
+    let state = common::database().await;
+    let steam_id = common::unique_steam_id();
+    stub.respond(SUMMARIES_PATH, 429, "{}");
+
+    let player = find_or_create_player(&state, &steam_id, Some("Hamlet"), None)
+        .await
+        .unwrap();
+
+    assert_eq!(player.steam_name, "Hamlet");
+    assert_eq!(enrichment_state(&state, player.id).await, Some((0, 0)));
+}
+
+#[actix_web::test]
+#[ignore = "needs a MySQL database in DATABASE_URL"]
+async fn unknown_steam_ids_are_rejected_even_with_a_nickname() {
+    let (stub, _guard) = common::services().await;
+    let state = common::database().await;
+    let steam_id = common::unique_steam_id();
+    stub.respond(SUMMARIES_PATH, 200, r#"{"response":{"players":[]}}"#);
+
+    let result = find_or_create_player(&state, &steam_id, Some("Hamlet"), None).await;
+
+    assert!(matches!(result, Err(AppError::PlayerSteamDoesNotExist)));
+    assert!(
+        players::fetch_one_by_steamid(&state, &steam_id)
+            .await
+            .is_err()
+    );
+}
+
+#[actix_web::test]
+#[ignore = "needs a MySQL database in DATABASE_URL"]
+async fn the_worker_fills_in_queued_profiles() {
+    let (stub, _guard) = common::services().await;
+    let state = common::database().await;
+    let player = common::insert_player(&state).await;
+    enrichments::create(&state, player.id).await.unwrap();
+    stub.respond(
+        SUMMARIES_PATH,
+        200,
+        &summaries(steam_id_64(&player.steam_id), "Ophelia"),
+    );
+
+    usecases::enrichments::enrich_due(&state).await.unwrap();
+
+    let enriched = players::fetch_one_by_id(&state, player.id).await.unwrap();
+    assert_eq!(enriched.steam_name, "Ophelia");
+    assert_eq!(
+        enriched.steam_avatar_url,
+        "https://avatars.example/full.jpg"
+    );
+    assert_eq!(enrichment_state(&state, player.id).await, None);
+}
+
+#[actix_web::test]
+#[ignore = "needs a MySQL database in DATABASE_URL"]
+async fn failed_enrichments_back_off_before_retrying() {
+    let (stub, _guard) = common::services().await;
+    let state = common::database().await;
+    let player = common::insert_player(&state).await;
+    let steam_id = steam_id_64(&player.steam_id).to_string();
+    enrichments::create(&state, player.id).await.unwrap();
+    stub.respond(SUMMARIES_PATH, 500, "{}");
+
+    usecases::enrichments::enrich_due(&state).await.unwrap();
+    assert_eq!(enrichment_state(&state, player.id).await, Some((1, 1)));
+
+    usecases::enrichments::enrich_due(&state).await.unwrap();
+    let attempts = stub
+        .requests(SUMMARIES_PATH)
+        .iter()
+        .filter(|request| request.query.contains(&steam_id))
+        .count();
+    assert_eq!(attempts, 1);
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use shion::{
+    adapters::webhook,
+    common::{events::Event, state::State},
+    models::events::PlayerCreated,
+    usecases::{events, webhooks},
+};
+
+use common::Stub;
+
+const SECRET: &str = "webhook-secret";
+
+fn expected_signature(payload: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(payload.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[actix_web::test]
+async fn deliveries_are_signed_with_the_webhook_secret() {
+    let stub = Stub::start();
+    let payload = r#"{"type":"player_created","data":{"id":1}}"#;
+
+    webhook::deliver(
+        &format!("{}/hook", stub.url),
+        SECRET,
+        7,
+        "player_created",
+        payload,
+    )
+    .await
+    .unwrap();
+
+    let requests = stub.requests("/hook");
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.body, payload);
+    assert_eq!(request.headers["x-shion-event"], "player_created");
+    assert_eq!(request.headers["x-shion-delivery"], "7");
+    assert_eq!(
+        request.headers["x-shion-signature"],
+        expected_signature(payload)
+    );
+}
+
+#[actix_web::test]
+async fn error_responses_fail_the_delivery() {
+    let stub = Stub::start();
+    stub.respond("/hook", 503, "{}");
+
+    let result = webhook::deliver(
+        &format!("{}/hook", stub.url),
+        SECRET,
+        1,
+        "player_created",
+        "{}",
+    )
+    .await;
+
+    assert!(result.is_err());
+}
+
+// Returns the attempts, whether it was delivered and whether it is backing off.
+async fn delivery_state(state: &State, webhook_id: u64) -> (u32, i64, i64) {
+    sqlx::query_as(
+        "SELECT attempts, CAST(delivered_at IS NOT NULL AS SIGNED), \
+         CAST(next_attempt_at > NOW() + INTERVAL 20 SECOND AS SIGNED) \
+         FROM `webhook_delivery` WHERE webhook_id = ?",
+    )
+    .bind(webhook_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+#[ignore = "needs a MySQL database in DATABASE_URL"]
+async fn published_events_are_stored_and_retried_with_backoff() {
+    let state = common::database().await;
+    let stub = Stub::start();
+    let path = format!("/hooks/{}", uuid::Uuid::new_v4());
+    let webhook_id =
+        sqlx::query("INSERT INTO `webhook` (url, secret, events) VALUES (?, ?, 'player_created')")
+            .bind(format!("{}{path}", stub.url))
+            .bind(SECRET)
+            .execute(&state.db)
+            .await
+            .unwrap()
+            .last_insert_id();
+
+    // Nothing subscribes to the bus here, the delivery has to come from the database.
+    events::publish(
+        &state,
+        Event::PlayerCreated(PlayerCreated {
+            id: 1,
+            steam_id: "STEAM_0:1:1".to_string(),
+            steam_name: "player".to_string(),
+            steam_avatar_url: String::new(),
+            country: "xx".to_string(),
+        }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(delivery_state(&state, webhook_id).await, (0, 0, 0));
+
+    stub.respond(&path, 500, "{}");
+    webhooks::deliver_due(&state).await.unwrap();
+    let requests = stub.requests(&path);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].headers["x-shion-signature"],
+        expected_signature(&requests[0].body)
+    );
+    assert_eq!(delivery_state(&state, webhook_id).await, (1, 0, 1));
+
+    // The failed delivery waits out its backoff before it is attempted again.
+    webhooks::deliver_due(&state).await.unwrap();
+    assert_eq!(stub.requests(&path).len(), 1);
+
+    sqlx::query("UPDATE `webhook_delivery` SET next_attempt_at = NOW() WHERE webhook_id = ?")
+        .bind(webhook_id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+    webhooks::deliver_due(&state).await.unwrap();
+    assert_eq!(stub.requests(&path).len(), 2);
+    assert_eq!(delivery_state(&state, webhook_id).await.1, 1);
+}